@@ -0,0 +1,119 @@
+use crate::capture::PacketData;
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// `--anonymize`: swaps every IP address in the decoded text for a stable pseudonym,
+/// so screenshots and exports can be shared while flows stay distinguishable.
+///
+/// Pseudonyms are handed out in order of first sighting from the benchmarking range
+/// (198.18.0.0/15) and the documentation prefix (2001:db8::/32), which can't be
+/// mistaken for real hosts. Broadcast, multicast, loopback and unspecified addresses
+/// identify nobody and are left alone.
+pub struct Anonymizer {
+    pseudonyms: HashMap<IpAddr, IpAddr>,
+    next_v4: u32,
+    next_v6: u128,
+}
+
+impl Anonymizer {
+    pub fn new() -> Self {
+        Self {
+            pseudonyms: HashMap::new(),
+            next_v4: 0,
+            next_v6: 0,
+        }
+    }
+
+    /// Rewrites the fields a packet is displayed, keyed and exported by. The raw frame
+    /// (hex dump, savefile) is left as captured.
+    pub fn apply(&mut self, packet: &mut PacketData) {
+        packet.source = self.rewrite(&packet.source);
+        packet.dest = self.rewrite(&packet.dest);
+        packet.summary = self.rewrite(&packet.summary);
+        packet.full_details = self.rewrite(&packet.full_details);
+    }
+
+    /// Replaces each address in `text`, including ones written with a port
+    /// ("1.2.3.4:80") or in brackets ("[::1]:443").
+    pub fn rewrite(&mut self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find(is_address_char) {
+            out.push_str(&rest[..start]);
+            rest = &rest[start..];
+            let end = rest.find(|c| !is_address_char(c)).unwrap_or(rest.len());
+            let run = &rest[..end];
+            match address_prefix(run) {
+                Some((addr, len)) => {
+                    out.push_str(&self.pseudonym(addr).to_string());
+                    out.push_str(&run[len..]);
+                }
+                None => out.push_str(run),
+            }
+            rest = &rest[end..];
+        }
+        out.push_str(rest);
+        out
+    }
+
+    fn pseudonym(&mut self, addr: IpAddr) -> IpAddr {
+        if !is_identifying(addr) {
+            return addr;
+        }
+        if let Some(known) = self.pseudonyms.get(&addr) {
+            return *known;
+        }
+        // Keep IPv4-mapped IPv6 in step with the plain IPv4 form (see --unmap-v4)
+        if let IpAddr::V6(v6) = addr
+            && let Some(v4) = v6.to_ipv4_mapped()
+        {
+            return match self.pseudonym(IpAddr::V4(v4)) {
+                IpAddr::V4(fake) => IpAddr::V6(fake.to_ipv6_mapped()),
+                other => other,
+            };
+        }
+        let fake = match addr {
+            IpAddr::V4(_) => {
+                // Skip the .0.0 network address; wraps only after 131070 hosts
+                let n = self.next_v4 % 0x1_FFFE + 1;
+                self.next_v4 += 1;
+                IpAddr::V4(Ipv4Addr::from(u32::from(Ipv4Addr::new(198, 18, 0, 0)) + n))
+            }
+            IpAddr::V6(_) => {
+                self.next_v6 += 1;
+                IpAddr::V6(Ipv6Addr::from(
+                    u128::from(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0)) + self.next_v6,
+                ))
+            }
+        };
+        self.pseudonyms.insert(addr, fake);
+        fake
+    }
+}
+
+fn is_address_char(c: char) -> bool {
+    c.is_ascii_hexdigit() || c == '.' || c == ':'
+}
+
+/// The address at the start of `run` and how many bytes it spans: the whole run, or
+/// the part before a trailing ":port" or sentence-ending '.'.
+fn address_prefix(run: &str) -> Option<(IpAddr, usize)> {
+    let trimmed = run.trim_end_matches(['.', ':']);
+    if let Ok(addr) = trimmed.parse::<IpAddr>() {
+        return Some((addr, trimmed.len()));
+    }
+    let (host, _port) = trimmed.rsplit_once(':')?;
+    match host.parse::<Ipv4Addr>() {
+        Ok(addr) => Some((IpAddr::V4(addr), host.len())),
+        Err(_) => None,
+    }
+}
+
+fn is_identifying(addr: IpAddr) -> bool {
+    match addr {
+        IpAddr::V4(v4) => {
+            !(v4.is_broadcast() || v4.is_multicast() || v4.is_loopback() || v4.is_unspecified())
+        }
+        IpAddr::V6(v6) => !(v6.is_multicast() || v6.is_loopback() || v6.is_unspecified()),
+    }
+}
@@ -1,11 +1,32 @@
+use crate::decoders::{
+    DecodedInfo, DnsMessage, decode_application, decode_link_layer, dhcp_summary, dns_message,
+    http_request, ports, smb_command, syslog_summary, text_payload, tls_hello,
+};
+use crate::oui;
+use crate::reassembly::{Fragment, Reassembler, reassembled_transport};
+use crate::rtp::RtpTracker;
+use crate::tcp::WindowTracker;
+use crate::wifi::{decode_wifi_frame, is_wireless};
 use chrono::{DateTime, Local};
-use etherparse::{NetSlice, SlicedPacket, TransportSlice};
-use std::time::Instant;
+use etherparse::err::packet::SliceError;
+use etherparse::{
+    ArpOperation, ArpPacketSlice, EtherType, LinkSlice, NetSlice, SingleVlanSlice, SlicedPacket,
+    TcpSlice, TransportSlice, VlanSlice,
+};
+use pcap::Linktype;
+use std::collections::VecDeque;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::time::{Duration, Instant};
 
+#[derive(Clone)]
 pub struct PacketData {
     pub summary: String,
     pub timestamp: Instant, // For the Spike Inspector math
     pub time_label: String, // For the UI display (HH:MM:SS)   pub summary: String,
+    /// pcap record time in µs since the epoch, for exports
+    pub captured_micros: i64,
     pub full_details: String,
     pub hex_dump: String,
     pub app_name: String,
@@ -13,15 +34,307 @@ pub struct PacketData {
     pub dest: String,
     pub proto_label: String,
     pub length: usize,
+    /// TCP/UDP (source, destination) ports, `None` for everything else
+    pub ports: Option<(u16, u16)>,
+    /// "TCP", "UDP" or "ICMP" when the frame carried one of those
+    pub transport: Option<&'static str>,
+    /// DSCP code point from the IPv4 ToS / IPv6 traffic class byte
+    pub dscp: Option<u8>,
+    /// Original frame bytes, only kept when `--retain`/`--retain-bytes` is on
+    pub raw: Option<Vec<u8>>,
+    /// Hash of the frame bytes, used to spot mirrored duplicates
+    pub fingerprint: u64,
+    /// Identical copies suppressed by `--dedup`
+    pub dup_count: u32,
+    /// Mirrored copy of a recent frame under `--dedup`: the UI credits the original's
+    /// `dup_count` and drops this one
+    pub duplicate: bool,
+    /// TCP segment advertising a zero receive window
+    pub zero_window: bool,
+    /// TLS hello negotiating SSL 3.0, TLS 1.0 or TLS 1.1
+    pub deprecated_tls: bool,
+    /// DNS transaction ID and question, for matching queries with responses
+    pub dns: Option<DnsMessage>,
+    /// How long this DNS response took after its query, once the tracker paired them
+    pub dns_latency: Option<Duration>,
+    /// Payload of a plaintext protocol (HTTP, SMTP, ...) decoded as lines
+    pub text: Option<String>,
+    /// Short detail shown after the label in the feed and summary (DNS question, TLS
+    /// server name). Kept out of `proto_label` so connection keys don't split on it.
+    pub info: Option<String>,
+    /// Two-letter country code of the remote end, with `--geoip`
+    pub country: Option<String>,
 }
 
-pub fn parse_packet_full(data: &[u8], app_name: String) -> Option<PacketData> {
-    let value = SlicedPacket::from_ethernet(data).ok()?;
+/// Cross-packet decoder state (fragment buffers, RTP streams, TCP window scales); one
+/// per capture source.
+pub struct ParseState {
+    defrag: Reassembler,
+    rtp: RtpTracker,
+    windows: WindowTracker,
+}
+
+impl ParseState {
+    pub fn new() -> Self {
+        Self {
+            defrag: Reassembler::new(),
+            rtp: RtpTracker::new(),
+            windows: WindowTracker::new(),
+        }
+    }
+}
+
+/// What the capture was opened on, for the header line; fixed once capture starts.
+pub struct SourceInfo {
+    /// Interface name, or the file being read
+    pub name: String,
+    pub linktype: Linktype,
+    pub addresses: Vec<String>,
+    /// (promiscuous, immediate mode); `None` for offline sources
+    pub modes: Option<(bool, bool)>,
+    /// BPF expression applied to the handle, if any
+    pub filter: Option<String>,
+}
+
+impl SourceInfo {
+    pub fn summary(&self) -> String {
+        let linktype = self
+            .linktype
+            .get_name()
+            .unwrap_or_else(|_| format!("DLT {}", self.linktype.0));
+        let mut parts = vec![self.name.clone(), linktype];
+        if !self.addresses.is_empty() {
+            parts.push(self.addresses.join(", "));
+        }
+        if let Some((promisc, immediate)) = self.modes {
+            let on_off = |b: bool| if b { "on" } else { "off" };
+            parts.push(format!("promisc {}", on_off(promisc)));
+            parts.push(format!("immediate {}", on_off(immediate)));
+        }
+        parts.push(match &self.filter {
+            Some(expr) => format!("BPF \"{}\"", expr),
+            None => "no BPF".into(),
+        });
+        parts.join(" | ")
+    }
+}
+
+/// Slices a frame according to the datalink type of the capture handle it came from.
+pub fn slice_packet(data: &[u8], linktype: Linktype) -> Result<SlicedPacket<'_>, SliceError> {
+    match linktype {
+        Linktype::LINUX_SLL => SlicedPacket::from_linux_sll(data),
+        // etherparse has no SLL2 slicer; the protocol field is an EtherType for
+        // everything this tool decodes, so slice the payload behind the header
+        Linktype::LINUX_SLL2 => {
+            let protocol = data
+                .get(..2)
+                .map_or(0, |p| u16::from_be_bytes([p[0], p[1]]));
+            let payload = data.get(SLL2_HEADER..).unwrap_or_default();
+            SlicedPacket::from_ether_type(EtherType(protocol), payload)
+        }
+        Linktype::RAW | Linktype::IPV4 | Linktype::IPV6 => SlicedPacket::from_ip(data),
+        // BSD/Npcap loopback: a 4-byte address family, then the IP packet
+        Linktype::NULL | Linktype::LOOP => {
+            let ip = data.get(4..).unwrap_or_default();
+            match loopback_family(data, linktype) {
+                Some(ether_type) => SlicedPacket::from_ether_type(ether_type, ip),
+                None => SlicedPacket::from_ip(ip),
+            }
+        }
+        _ => SlicedPacket::from_ethernet(data),
+    }
+}
+
+const SLL_HEADER: usize = 16;
+const SLL2_HEADER: usize = 20;
+
+/// The Linux "cooked" header the `any` device puts in front of every packet instead
+/// of an Ethernet header: which way the packet went and the sender's link address.
+struct CookedHeader<'a> {
+    packet_type: u16,
+    protocol: u16,
+    /// Only SLL2 records the interface
+    ifindex: Option<u32>,
+    sender: &'a [u8],
+}
+
+impl<'a> CookedHeader<'a> {
+    fn parse(data: &'a [u8], linktype: Linktype) -> Option<Self> {
+        let be16 = |at: usize| u16::from_be_bytes([data[at], data[at + 1]]);
+        match linktype {
+            // type(2) hatype(2) addr len(2) addr(8) protocol(2)
+            Linktype::LINUX_SLL if data.len() >= SLL_HEADER => Some(Self {
+                packet_type: be16(0),
+                protocol: be16(14),
+                ifindex: None,
+                sender: &data[6..6 + (be16(4) as usize).min(8)],
+            }),
+            // protocol(2) reserved(2) ifindex(4) hatype(2) type(1) addr len(1) addr(8)
+            Linktype::LINUX_SLL2 if data.len() >= SLL2_HEADER => Some(Self {
+                packet_type: data[10] as u16,
+                protocol: be16(0),
+                ifindex: Some(u32::from_be_bytes([data[4], data[5], data[6], data[7]])),
+                sender: &data[12..12 + (data[11] as usize).min(8)],
+            }),
+            _ => None,
+        }
+    }
+
+    fn direction(&self) -> String {
+        match self.packet_type {
+            0 => "incoming (to us)".into(),
+            1 => "incoming (broadcast)".into(),
+            2 => "incoming (multicast)".into(),
+            3 => "incoming (to another host)".into(),
+            4 => "outgoing".into(),
+            other => format!("type {}", other),
+        }
+    }
+
+    /// The sender as a MAC when it is one, otherwise the raw address bytes.
+    fn sender(&self) -> String {
+        if self.sender.len() == 6 {
+            oui::format_with_vendor(self.sender)
+        } else if self.sender.is_empty() {
+            "none".into()
+        } else {
+            format_mac(self.sender)
+        }
+    }
+
+    fn details(&self) -> String {
+        let mut details = format!(
+            "--- LINK LAYER ---\nHeader:   Linux cooked ({})\nPacket:   {}\nSrc addr: {}\n",
+            if self.ifindex.is_some() {
+                "SLL2"
+            } else {
+                "SLL"
+            },
+            self.direction(),
+            self.sender()
+        );
+        if let Some(ifindex) = self.ifindex {
+            details.push_str(&format!("Ifindex:  {}\n", ifindex));
+        }
+        details.push('\n');
+        details
+    }
+}
+
+/// Reads a NULL/LOOP header's address family. LOOP stores it big-endian; NULL uses
+/// the capturing host's byte order, so both are tried. IPv6's number differs per OS.
+fn loopback_family(data: &[u8], linktype: Linktype) -> Option<EtherType> {
+    let header: [u8; 4] = data.get(..4)?.try_into().ok()?;
+    let family = match linktype {
+        Linktype::LOOP => u32::from_be_bytes(header),
+        // Families are small, so the wrong byte order gives a huge number
+        _ => u32::from_le_bytes(header).min(u32::from_be_bytes(header)),
+    };
+    match family {
+        2 => Some(EtherType::IPV4),
+        // Linux, Windows, NetBSD/OpenBSD, FreeBSD, macOS
+        10 | 23 | 24 | 28 | 30 => Some(EtherType::IPV6),
+        _ => None,
+    }
+}
+
+/// Rewrites an IPv4-mapped IPv6 address (`::ffff:1.2.3.4`) as plain IPv4, so a
+/// dual-stack socket's traffic lands on the same host as its v4 counterpart. Anything
+/// else comes back unchanged.
+pub fn unmap_ipv4(addr: &str) -> String {
+    match addr
+        .parse::<Ipv6Addr>()
+        .ok()
+        .and_then(|v6| v6.to_ipv4_mapped())
+    {
+        Some(v4) => v4.to_string(),
+        None => addr.to_string(),
+    }
+}
+
+/// Wall-clock `HH:MM:SS` of a pcap record timestamp (microseconds since the epoch).
+pub fn time_label(micros: i64) -> String {
+    match DateTime::from_timestamp_micros(micros) {
+        Some(at) => at.with_timezone(&Local).format("%H:%M:%S").to_string(),
+        None => Local::now().format("%H:%M:%S").to_string(),
+    }
+}
+
+/// Formats raw hardware address bytes as `aa:bb:cc:dd:ee:ff`.
+pub fn format_mac(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Decodes a captured frame. Frames that can't be sliced still come back as an
+/// `[unparsed]` entry so nothing silently disappears from the feed.
+///
+/// IP fragments are buffered in `state`; the fragment that completes a datagram is
+/// decoded with the transport and application layers of the whole datagram. The
+/// returned `app_name` is always "Unknown" — the caller attributes it from `ports`.
+pub fn parse_packet_full(data: &[u8], linktype: Linktype, state: &mut ParseState) -> PacketData {
+    // Monitor-mode captures are 802.11 frames, not Ethernet
+    if is_wireless(linktype) {
+        return match decode_wifi_frame(data, linktype) {
+            Some(frame) => build_packet_data(
+                data,
+                frame.source,
+                frame.dest,
+                frame.proto_label,
+                frame.details,
+            ),
+            None => {
+                log::debug!("malformed {} byte 802.11 frame", data.len());
+                build_unparsed(data, linktype, "malformed 802.11 frame".into())
+            }
+        };
+    }
+
+    let value = match slice_packet(data, linktype) {
+        Ok(value) => value,
+        Err(e) => {
+            log::debug!("unparsed {} byte frame: {}", data.len(), e);
+            return build_unparsed(data, linktype, e.to_string());
+        }
+    };
 
     let mut source = String::from("Unknown");
     let mut dest = String::from("Unknown");
     let mut proto_label = String::from("DATA");
     let mut details = String::new();
+    let mut dscp = None;
+    // Shown after the label when no DNS question or TLS server name claims the spot
+    let mut info = None;
+
+    // --- LINK LAYER ---
+    let mut link_header_shown = false;
+    if let Some(LinkSlice::Ethernet2(eth)) = &value.link {
+        details.push_str(&format!(
+            "--- LINK LAYER ---\nSrc MAC:  {}\nDst MAC:  {}\n\n",
+            oui::format_with_vendor(&eth.source()),
+            oui::format_with_vendor(&eth.destination())
+        ));
+        link_header_shown = true;
+    } else if let Some(cooked) = CookedHeader::parse(data, linktype) {
+        details.push_str(&cooked.details());
+        link_header_shown = true;
+    }
+
+    // --- VLAN ---
+    if let Some(vlan) = value.vlan() {
+        details.push_str("--- VLAN ---\n");
+        match &vlan {
+            VlanSlice::SingleVlan(tag) => details.push_str(&vlan_tag("Tag:     ", tag)),
+            VlanSlice::DoubleVlan(tags) => {
+                details.push_str(&vlan_tag("Outer:   ", &tags.outer));
+                details.push_str(&vlan_tag("Inner:   ", &tags.inner));
+            }
+        }
+        details.push('\n');
+    }
 
     // --- NETWORK LAYER ---
     if let Some(net) = &value.net {
@@ -36,50 +349,126 @@ pub fn parse_packet_full(data: &[u8], app_name: String) -> Option<PacketData> {
                     dest,
                     ipv4.header().ttl()
                 ));
+                let header = ipv4.header();
+                details.push_str(&format_qos(header.dcp().value(), header.ecn().value()));
+                dscp = Some(header.dcp().value());
             }
             NetSlice::Ipv6(ipv6) => {
-                source = format!("{:?}", ipv6.header().source_addr());
-                dest = format!("{:?}", ipv6.header().destination_addr());
+                // Display is the RFC 5952 canonical form: lowercase, longest zero run
+                // compressed, embedded IPv4 in dotted form
+                source = ipv6.header().source_addr().to_string();
+                dest = ipv6.header().destination_addr().to_string();
                 details.push_str(&format!(
                     "Protocol: IPv6\nSource:   {}\nDest:     {}\n",
                     source, dest
                 ));
+                let header = ipv6.header();
+                details.push_str(&format_qos(header.dscp().value(), header.ecn().value()));
+                dscp = Some(header.dscp().value());
             }
-            // FIX: Handling ARP Packets
             NetSlice::Arp(arp) => {
                 proto_label = "ARP".into();
-                source = format!("{:X?}", arp.sender_hw_addr());
-                dest = format!("{:X?}", arp.target_hw_addr());
+                source = format_mac(arp.sender_hw_addr());
+                // A request's target MAC is the unknown being asked for; the frame's
+                // own destination (usually broadcast) says more
+                dest = match &value.link {
+                    Some(LinkSlice::Ethernet2(eth)) => format_mac(&eth.destination()),
+                    _ => format_mac(arp.target_hw_addr()),
+                };
+                let sender_ip = arp_protocol_addr(arp, arp.sender_protocol_addr());
+                let target_ip = arp_protocol_addr(arp, arp.target_protocol_addr());
+                info = Some(match arp.operation() {
+                    ArpOperation::REQUEST if sender_ip == target_ip => {
+                        format!("announce {}", sender_ip)
+                    }
+                    ArpOperation::REQUEST => format!("who-has {} tell {}", target_ip, sender_ip),
+                    ArpOperation::REPLY => format!("{} is-at {}", sender_ip, source),
+                    other => format!("operation {}", other.0),
+                });
                 details.push_str(&format!(
-                    "Protocol: ARP (Address Resolution)\nSender MAC: {:X?}\nTarget MAC: {:X?}\n",
-                    arp.sender_hw_addr(),
-                    arp.target_hw_addr()
+                    "Protocol: ARP (Address Resolution)\nOperation:  {}\nSender MAC: {}\nSender IP:  {}\nTarget MAC: {}\nTarget IP:  {}\n",
+                    match arp.operation() {
+                        ArpOperation::REQUEST => "request".to_string(),
+                        ArpOperation::REPLY => "reply".to_string(),
+                        other => other.0.to_string(),
+                    },
+                    source,
+                    sender_ip,
+                    format_mac(arp.target_hw_addr()),
+                    target_ip
                 ));
             }
         }
     }
 
+    // --- LINK LAYER (neighbor discovery and other non-IP payloads) ---
+    if value.net.is_none()
+        && let Some(payload) = value.ether_payload()
+        && let Some(decoded) = decode_link_layer(&payload)
+    {
+        if let Some(LinkSlice::Ethernet2(eth)) = &value.link {
+            source = format_mac(&eth.source());
+            dest = format_mac(&eth.destination());
+        } else if let Some(cooked) = CookedHeader::parse(data, linktype)
+            && !cooked.sender.is_empty()
+        {
+            // Cooked frames only record the sender
+            source = format_mac(cooked.sender);
+        }
+        proto_label = decoded.label;
+        if !link_header_shown {
+            details.push_str("--- LINK LAYER ---\n");
+        }
+        details.push_str(&decoded.details);
+    }
+
+    // --- FRAGMENTS ---
+    let reassembled = match state.defrag.push(&value) {
+        Fragment::Whole => None,
+        Fragment::Pending => {
+            proto_label = "IP-FRAG".into();
+            details.push_str("Fragment: buffered, waiting for the rest of the datagram\n");
+            None
+        }
+        Fragment::Complete(payload) => {
+            details.push_str(&format!(
+                "Fragment: last one, reassembled {} byte datagram\n",
+                payload.payload.len()
+            ));
+            Some(payload)
+        }
+        Fragment::Invalid(error) => {
+            log::debug!("discarded IP fragment: {}", error);
+            proto_label = "IP-FRAG".into();
+            details.push_str(&format!("Fragment: discarded ({})\n", error));
+            None
+        }
+    };
+    let transport = match &reassembled {
+        Some(payload) => reassembled_transport(payload),
+        None => value.transport.clone(),
+    };
+
     // --- TRANSPORT LAYER ---
-    if let Some(transport) = &value.transport {
+    let mut zero_window = false;
+    if let Some(transport) = &transport {
+        proto_label = guess_protocol(transport);
         details.push_str("\n--- TRANSPORT LAYER ---\n");
         match transport {
             TransportSlice::Tcp(tcp) => {
-                let port = tcp.destination_port();
-                proto_label = match port {
-                    80 => "HTTP".into(),
-                    443 => "HTTPS".into(),
-                    _ => format!("TCP:{}", port),
-                };
                 details.push_str(&format!(
-                    "Type:  TCP\nPorts: {} -> {}\nSeq:   {}\nAck:   {}\n",
+                    "Type:  TCP\nPorts: {} -> {}\nSeq:   {}\nAck:   {}\nFlags: {}\n",
                     tcp.source_port(),
                     tcp.destination_port(),
                     tcp.sequence_number(),
-                    tcp.acknowledgment_number()
+                    tcp.acknowledgment_number(),
+                    tcp_flags(tcp)
                 ));
+                let window = state.windows.observe(&source, &dest, tcp, Instant::now());
+                zero_window = window.zero;
+                details.push_str(&window.details());
             }
             TransportSlice::Udp(udp) => {
-                proto_label = format!("UDP:{}", udp.destination_port());
                 details.push_str(&format!(
                     "Type:  UDP\nPorts: {} -> {}\nLen:   {}\n",
                     udp.source_port(),
@@ -87,55 +476,447 @@ pub fn parse_packet_full(data: &[u8], app_name: String) -> Option<PacketData> {
                     udp.length()
                 ));
             }
-            // FIX: Handling ICMP (Ping)
             TransportSlice::Icmpv4(icmp) => {
-                proto_label = "ICMPv4".into();
-                details.push_str(&format!(
-                    "Type:  ICMPv4\nCode:  {:?}\n",
-                    icmp.header().icmp_type
-                ));
+                let message = IcmpMessage::v4(icmp.type_u8(), icmp.code_u8(), icmp.bytes5to8());
+                details.push_str(&message.details("ICMPv4"));
+                info = Some(message.summary());
             }
             TransportSlice::Icmpv6(icmp) => {
-                proto_label = "ICMPv6".into();
-                details.push_str(&format!(
-                    "Type:  ICMPv6\nCode:  {:?}\n",
-                    icmp.header().icmp_type
-                ));
+                let message = IcmpMessage::v6(icmp.type_u8(), icmp.code_u8(), icmp.bytes5to8());
+                details.push_str(&message.details("ICMPv6"));
+                info = Some(message.summary());
             }
         }
     }
 
-    let summary = format!("{:<15} -> {:<15} | {:^10}", source, dest, proto_label);
+    // --- APPLICATION LAYER ---
+    if let Some(transport) = &transport {
+        // RTP has no port of its own: fall back to the stream heuristic on even,
+        // unprivileged UDP port pairs once no decoder has claimed the payload
+        let decoded = decode_application(transport).or_else(|| match transport {
+            TransportSlice::Udp(udp)
+                if [udp.source_port(), udp.destination_port()]
+                    .iter()
+                    .all(|p| p % 2 == 0 && *p >= 1024) =>
+            {
+                state
+                    .rtp
+                    .observe(udp.payload(), Instant::now())
+                    .map(|rtp| DecodedInfo {
+                        label: rtp.label,
+                        details: rtp.details,
+                    })
+            }
+            _ => None,
+        });
+        if let Some(decoded) = decoded {
+            proto_label = decoded.label;
+            details.push_str("\n--- APPLICATION LAYER ---\n");
+            details.push_str(&decoded.details);
+        }
+    }
 
+    let mut packet = build_packet_data(data, source, dest, proto_label, details);
+    packet.ports = transport.as_ref().and_then(ports);
+    packet.transport = transport.as_ref().map(|t| match t {
+        TransportSlice::Tcp(_) => "TCP",
+        TransportSlice::Udp(_) => "UDP",
+        TransportSlice::Icmpv4(_) | TransportSlice::Icmpv6(_) => "ICMP",
+    });
+    packet.dscp = dscp;
+    packet.zero_window = zero_window;
+    packet.dns = transport.as_ref().and_then(dns_message);
+    let hello = transport.as_ref().and_then(tls_hello);
+    packet.deprecated_tls = hello.as_ref().is_some_and(|hello| hello.deprecated);
+    packet.info = match packet.dns.as_ref().and_then(DnsMessage::question) {
+        Some(question) => Some(question),
+        None => hello
+            .map(|hello| hello.summary)
+            .or_else(|| transport.as_ref().and_then(http_request))
+            .or_else(|| transport.as_ref().and_then(dhcp_summary))
+            .or_else(|| transport.as_ref().and_then(smb_command))
+            .or_else(|| transport.as_ref().and_then(syslog_summary))
+            .or(info),
+    };
+    if let Some(info) = &packet.info {
+        packet.summary.push_str(&format!(" {}", info));
+    }
+    packet.text = transport.as_ref().and_then(text_payload);
+    packet
+}
+
+/// "Tag:      VLAN 100, priority 3" for one 802.1Q tag; DEI is only mentioned when set.
+fn vlan_tag(name: &str, tag: &SingleVlanSlice) -> String {
+    format!(
+        "{} VLAN {}, priority {}{}\n",
+        name,
+        tag.vlan_identifier().value(),
+        tag.priority_code_point().value(),
+        if tag.drop_eligible_indicator() {
+            ", drop eligible"
+        } else {
+            ""
+        }
+    )
+}
+
+/// The control flags set on a segment, e.g. "SYN ACK", in tcpdump's order.
+fn tcp_flags(tcp: &TcpSlice) -> String {
+    let flags = [
+        (tcp.syn(), "SYN"),
+        (tcp.fin(), "FIN"),
+        (tcp.rst(), "RST"),
+        (tcp.psh(), "PSH"),
+        (tcp.ack(), "ACK"),
+        (tcp.urg(), "URG"),
+        (tcp.ece(), "ECE"),
+        (tcp.cwr(), "CWR"),
+    ];
+    let set: Vec<&str> = flags
+        .iter()
+        .filter(|(on, _)| *on)
+        .map(|(_, name)| *name)
+        .collect();
+    if set.is_empty() {
+        "none".into()
+    } else {
+        set.join(" ")
+    }
+}
+
+/// ICMP type and code with their names, plus the fields worth surfacing for ping,
+/// traceroute and path-MTU debugging.
+struct IcmpMessage {
+    icmp_type: u8,
+    code: u8,
+    name: Option<&'static str>,
+    code_name: Option<&'static str>,
+    /// Identifier and sequence number of an echo request/reply
+    echo: Option<(u16, u16)>,
+    /// Next-hop MTU of "fragmentation needed" / "packet too big"
+    mtu: Option<u32>,
+}
+
+impl IcmpMessage {
+    fn v4(icmp_type: u8, code: u8, rest: [u8; 4]) -> Self {
+        let name = match icmp_type {
+            0 => Some("echo-reply"),
+            3 => Some("dest-unreachable"),
+            5 => Some("redirect"),
+            8 => Some("echo-request"),
+            9 => Some("router-advert"),
+            10 => Some("router-solicit"),
+            11 => Some("time-exceeded"),
+            12 => Some("param-problem"),
+            13 => Some("timestamp"),
+            14 => Some("timestamp-reply"),
+            _ => None,
+        };
+        let code_name = match (icmp_type, code) {
+            (3, 0) => Some("net"),
+            (3, 1) => Some("host"),
+            (3, 2) => Some("protocol"),
+            (3, 3) => Some("port"),
+            (3, 4) => Some("frag-needed"),
+            (3, 5) => Some("source-route-failed"),
+            (3, 6) => Some("net-unknown"),
+            (3, 7) => Some("host-unknown"),
+            (3, 9) | (3, 10) | (3, 13) => Some("admin-prohibited"),
+            (5, 0) => Some("net"),
+            (5, 1) => Some("host"),
+            (11, 0) => Some("ttl"),
+            (11, 1) => Some("reassembly"),
+            _ => None,
+        };
+        Self {
+            icmp_type,
+            code,
+            name,
+            code_name,
+            echo: matches!(icmp_type, 0 | 8).then(|| echo_fields(rest)),
+            mtu: (icmp_type == 3 && code == 4)
+                .then(|| u16::from_be_bytes([rest[2], rest[3]]) as u32),
+        }
+    }
+
+    fn v6(icmp_type: u8, code: u8, rest: [u8; 4]) -> Self {
+        let name = match icmp_type {
+            1 => Some("dest-unreachable"),
+            2 => Some("packet-too-big"),
+            3 => Some("time-exceeded"),
+            4 => Some("param-problem"),
+            128 => Some("echo-request"),
+            129 => Some("echo-reply"),
+            130 => Some("mld-query"),
+            131 => Some("mld-report"),
+            133 => Some("router-solicit"),
+            134 => Some("router-advert"),
+            135 => Some("neighbor-solicit"),
+            136 => Some("neighbor-advert"),
+            137 => Some("redirect"),
+            143 => Some("mldv2-report"),
+            _ => None,
+        };
+        let code_name = match (icmp_type, code) {
+            (1, 0) => Some("no-route"),
+            (1, 1) => Some("admin-prohibited"),
+            (1, 2) => Some("beyond-scope"),
+            (1, 3) => Some("address"),
+            (1, 4) => Some("port"),
+            (1, 5) => Some("policy-failed"),
+            (1, 6) => Some("reject-route"),
+            (3, 0) => Some("hop-limit"),
+            (3, 1) => Some("reassembly"),
+            _ => None,
+        };
+        Self {
+            icmp_type,
+            code,
+            name,
+            code_name,
+            echo: matches!(icmp_type, 128 | 129).then(|| echo_fields(rest)),
+            mtu: (icmp_type == 2).then(|| u32::from_be_bytes(rest)),
+        }
+    }
+
+    /// Short form for the feed, e.g. "dest-unreachable (port)" or "echo-request seq 7".
+    fn summary(&self) -> String {
+        let mut text = match self.name {
+            Some(name) => name.to_string(),
+            None => format!("type {}", self.icmp_type),
+        };
+        match (self.code_name, self.code) {
+            (Some(code), _) => text.push_str(&format!(" ({})", code)),
+            (None, 0) => {}
+            (None, code) => text.push_str(&format!(" (code {})", code)),
+        }
+        if let Some((_, seq)) = self.echo {
+            text.push_str(&format!(" seq {}", seq));
+        }
+        if let Some(mtu) = self.mtu {
+            text.push_str(&format!(" mtu {}", mtu));
+        }
+        text
+    }
+
+    /// TRANSPORT LAYER lines.
+    fn details(&self, version: &str) -> String {
+        let mut text = format!(
+            "Type:  {} {} ({})\nCode:  {}",
+            version,
+            self.icmp_type,
+            self.name.unwrap_or("unknown"),
+            self.code
+        );
+        if let Some(code) = self.code_name {
+            text.push_str(&format!(" ({})", code));
+        }
+        text.push('\n');
+        if let Some((id, seq)) = self.echo {
+            text.push_str(&format!("Echo:  id {} seq {}\n", id, seq));
+        }
+        if let Some(mtu) = self.mtu {
+            text.push_str(&format!("MTU:   {} (next hop)\n", mtu));
+        }
+        text
+    }
+}
+
+/// Identifier and sequence number from the second word of an echo message.
+fn echo_fields(rest: [u8; 4]) -> (u16, u16) {
+    (
+        u16::from_be_bytes([rest[0], rest[1]]),
+        u16::from_be_bytes([rest[2], rest[3]]),
+    )
+}
+
+/// An ARP sender/target protocol address: dotted IPv4 for IPv4-over-anything, hex
+/// bytes for the rare other protocol types.
+fn arp_protocol_addr(arp: &ArpPacketSlice, addr: &[u8]) -> String {
+    match <[u8; 4]>::try_from(addr) {
+        Ok(octets) if arp.proto_addr_type() == EtherType::IPV4 => {
+            Ipv4Addr::from(octets).to_string()
+        }
+        _ => format_mac(addr),
+    }
+}
+
+/// DSCP class and ECN state lines for the NETWORK LAYER section.
+fn format_qos(dscp: u8, ecn: u8) -> String {
+    let class = match dscp {
+        0 => "CS0".to_string(),
+        1 => "LE".to_string(),
+        44 => "VOICE-ADMIT".to_string(),
+        46 => "EF".to_string(),
+        // AFxy: x = class (1-4), y = drop precedence (1-3), encoded as 8x + 2y
+        d if d % 2 == 0 && (10..=38).contains(&d) && (1..=3).contains(&((d % 8) / 2)) => {
+            format!("AF{}{}", d / 8, (d % 8) / 2)
+        }
+        d if d % 8 == 0 => format!("CS{}", d / 8),
+        d => d.to_string(),
+    };
+    let ecn = match ecn {
+        0 => "Not-ECT",
+        1 => "ECT(1)",
+        2 => "ECT(0)",
+        _ => "CE (congestion experienced)",
+    };
+    format!("DSCP:     {} ({})\nECN:      {}\n", class, dscp, ecn)
+}
+
+/// EF, CS5 and above, and AF4x: the classes voice/video and network control ride in.
+pub fn is_high_priority(dscp: u8) -> bool {
+    dscp >= 40 || (34..=38).contains(&dscp)
+}
+
+/// Fallback for frames etherparse rejects: whatever the link header tells us, plus the error.
+fn build_unparsed(data: &[u8], linktype: Linktype, error: String) -> PacketData {
+    let (source, dest, ethertype) = match linktype {
+        Linktype::ETHERNET if data.len() >= 14 => (
+            format_mac(&data[6..12]),
+            format_mac(&data[0..6]),
+            Some(u16::from_be_bytes([data[12], data[13]])),
+        ),
+        _ => match CookedHeader::parse(data, linktype) {
+            Some(cooked) => (
+                format_mac(cooked.sender),
+                String::from("Unknown"),
+                Some(cooked.protocol),
+            ),
+            None => (String::from("Unknown"), String::from("Unknown"), None),
+        },
+    };
+
+    let mut details = format!(
+        "--- UNPARSED FRAME ---\nLink Type:  {}\nLength:     {} bytes\n",
+        linktype
+            .get_name()
+            .unwrap_or_else(|_| format!("DLT {}", linktype.0)),
+        data.len()
+    );
+    if let Some(ethertype) = ethertype {
+        details.push_str(&format!("EtherType:  0x{:04x}\n", ethertype));
+    }
+    details.push_str(&format!("Error:      {}\n", error));
+
+    let mut packet = build_packet_data(data, source, dest, "UNPARSED".into(), details);
+    packet.summary = match ethertype {
+        Some(ethertype) => format!(
+            "[unparsed] {} bytes ethertype=0x{:04x}",
+            data.len(),
+            ethertype
+        ),
+        None => format!("[unparsed] {} bytes", data.len()),
+    };
+    packet
+}
+
+/// Port-based fallback label for segments no application decoder claimed.
+fn guess_protocol(transport: &TransportSlice) -> String {
+    match transport {
+        TransportSlice::Tcp(tcp) => match tcp.destination_port() {
+            80 => "HTTP".into(),
+            443 => "HTTPS".into(),
+            445 => "SMB".into(),
+            139 => "NetBIOS".into(),
+            port => format!("TCP:{}", port),
+        },
+        TransportSlice::Udp(udp) => match udp.destination_port() {
+            137 | 138 => "NetBIOS".into(),
+            // Anything the DHCP decoder couldn't read (no magic cookie, truncated)
+            67 | 68 => "DHCP".into(),
+            5353 => "mDNS".into(),
+            5355 => "LLMNR".into(),
+            port => format!("UDP:{}", port),
+        },
+        TransportSlice::Icmpv4(_) => "ICMPv4".into(),
+        TransportSlice::Icmpv6(_) => "ICMPv6".into(),
+    }
+}
+
+fn build_packet_data(
+    data: &[u8],
+    source: String,
+    dest: String,
+    proto_label: String,
+    details: String,
+) -> PacketData {
+    // Plain text for search and --headless; the feed lays out its own columns
+    let summary = format!("{} -> {} | {}", source, dest, proto_label);
+
+    // `hexdump -C` layout: offset, two groups of eight bytes, printable ASCII
     let hex_dump = data
         .chunks(16)
-        .map(|chunk| {
+        .enumerate()
+        .map(|(row, chunk)| {
             let hex = chunk
-                .iter()
-                .map(|b| format!("{:02X}", b))
+                .chunks(8)
+                .map(|half| {
+                    half.iter()
+                        .map(|b| format!("{:02X}", b))
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                })
                 .collect::<Vec<_>>()
-                .join(" ");
+                .join("  ");
             let ascii: String = chunk
                 .iter()
-                .map(|&b| if b >= 32 && b <= 126 { b as char } else { '.' })
+                .map(|&b| {
+                    if (32..=126).contains(&b) {
+                        b as char
+                    } else {
+                        '.'
+                    }
+                })
                 .collect();
-            format!("{:<48} | {}", hex, ascii)
+            format!("{:04X}  {:<49} |{}|", row * 16, hex, ascii)
         })
         .collect::<Vec<_>>()
         .join("\n");
 
     let length = data.len();
 
-    Some(PacketData {
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+
+    PacketData {
         timestamp: Instant::now(),
         time_label: Local::now().format("%H:%M:%S").to_string(),
+        captured_micros: Local::now().timestamp_micros(),
         summary,
         full_details: details,
         hex_dump,
-        app_name,
+        app_name: String::from("Unknown"),
         source,
         dest,
         proto_label,
         length,
-    })
+        ports: None,
+        transport: None,
+        dscp: None,
+        raw: None,
+        fingerprint: hasher.finish(),
+        dup_count: 0,
+        deprecated_tls: false,
+        duplicate: false,
+        zero_window: false,
+        dns: None,
+        dns_latency: None,
+        text: None,
+        info: None,
+        country: None,
+    }
+}
+
+/// Drops retained frame bytes, oldest packet first, until `used` fits in `budget`.
+/// The packets themselves stay in the feed; only their raw copy goes.
+pub fn enforce_raw_budget(packets: &mut VecDeque<PacketData>, used: &mut usize, budget: usize) {
+    for packet in packets.iter_mut() {
+        if *used <= budget {
+            break;
+        }
+        if let Some(raw) = packet.raw.take() {
+            *used -= raw.len();
+        }
+    }
 }
@@ -0,0 +1,319 @@
+use crate::KeyGranularity;
+use std::env;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Command-line options. Anything not given here falls back to the interactive prompts.
+#[derive(Debug)]
+pub struct Args {
+    /// Capture device to open instead of prompting for one
+    pub interface: Option<String>,
+    /// Print the capture devices and exit
+    pub list_interfaces: bool,
+    /// BPF expression compiled onto the capture handle, so the kernel discards the rest
+    pub filter: Option<String>,
+    /// Datalink type name (e.g. "EN10MB", "RAW", "LINUX_SLL") to force on the capture handle
+    pub dlt: Option<String>,
+    /// Key binding overrides (see `keymap::KeyMap::load` for the format)
+    pub keymap: Option<PathBuf>,
+    /// Total budget for raw frame bytes kept alongside each packet; `None` disables retention
+    pub retain_bytes: Option<usize>,
+    /// Suppress identical frames seen again within a short window (mirrored ports)
+    pub dedup: bool,
+    /// Ceiling in bytes/s for the fixed throughput-chart scale (e.g. the link speed)
+    pub chart_ceiling: u64,
+    /// Stop capturing and exit cleanly after this long
+    pub duration: Option<Duration>,
+    /// Print the feed to stdout instead of starting the TUI
+    pub headless: bool,
+    /// Two capture files whose connection sets should be compared instead of sniffing
+    pub diff: Option<(PathBuf, PathBuf)>,
+    /// Clear captured data immediately instead of asking y/n first
+    pub no_confirm: bool,
+    /// Upper bound on UI redraws per second, for slow terminals
+    pub fps: Option<u32>,
+    /// New connections per second at which the footer counter turns red
+    pub new_conn_alert: Option<u64>,
+    /// File that receives diagnostics (capture errors, parse failures, drops)
+    pub log: Option<PathBuf>,
+    /// CSV that every completed throughput second is appended to
+    pub throughput_log: Option<PathBuf>,
+    /// Capture file to read instead of a live interface; `-` reads a pcap stream on stdin
+    pub read: Option<PathBuf>,
+    /// Key connections by the IPv4 form of IPv4-mapped IPv6 addresses
+    pub unmap_v4: bool,
+    /// Start with SSDP and other discovery chatter shown (see `noise.rs`)
+    pub show_multicast: bool,
+    /// Seconds averaged for the throughput chart's rolling-average readout
+    pub avg_window: usize,
+    /// One-second throughput buckets the chart keeps
+    pub history_seconds: usize,
+    /// Memory budget in bytes for the feed and connections table; `None` means unbounded
+    pub max_mem: Option<usize>,
+    /// IEEE `oui.txt` or Wireshark `manuf` file for MAC vendor names
+    pub oui_file: Option<PathBuf>,
+    /// MaxMind DB (e.g. GeoLite2-Country.mmdb) for tagging remote hosts with a country
+    pub geoip: Option<PathBuf>,
+    /// Initial grouping for the Connections tab
+    pub granularity: KeyGranularity,
+    /// Playback speed multiplier for `--read` (1.0 = as recorded); `None` reads as fast
+    /// as possible
+    pub replay_speed: Option<f64>,
+    /// Count throughput when the UI drains a packet instead of when it was captured
+    pub buffered_accounting: bool,
+    /// Parsed packets allowed to queue between the capture thread and the UI
+    pub channel_capacity: usize,
+    /// Characters of a plaintext payload shown in the inspector before it's cut off
+    pub text_limit: usize,
+    /// Rotate the `w` savefile and keep only this many files on disk
+    pub ring_files: Option<usize>,
+    /// Start a new `w` savefile once the current one reaches this many bytes
+    pub rotate_bytes: Option<u64>,
+    /// Start a new `w` savefile once the current one has been open this long
+    pub rotate_every: Option<Duration>,
+    /// Show stable pseudonyms instead of real IP addresses everywhere on screen and in exports
+    pub anonymize: bool,
+}
+
+/// 1 Gbit/s expressed in bytes/s.
+const DEFAULT_CHART_CEILING: u64 = 125_000_000;
+
+/// Chart history used when `--history-seconds` isn't given.
+const DEFAULT_HISTORY_SECONDS: usize = 200;
+
+/// Rolling-average window used when `--avg-window` isn't given.
+const DEFAULT_AVG_WINDOW: usize = 10;
+
+/// Capture-to-UI queue length used when `--channel-capacity` isn't given.
+const DEFAULT_CHANNEL_CAPACITY: usize = 10_000;
+
+/// Plaintext payload shown in the inspector when `--text-limit` isn't given.
+const DEFAULT_TEXT_LIMIT: usize = 4096;
+
+/// Raw-frame budget used by `--retain` when no explicit size is given.
+const DEFAULT_RETAIN_BYTES: usize = 64 * 1024 * 1024;
+
+impl Default for Args {
+    fn default() -> Self {
+        Self {
+            interface: None,
+            list_interfaces: false,
+            filter: None,
+            dlt: None,
+            keymap: None,
+            retain_bytes: None,
+            dedup: false,
+            chart_ceiling: DEFAULT_CHART_CEILING,
+            duration: None,
+            headless: false,
+            diff: None,
+            no_confirm: false,
+            fps: None,
+            new_conn_alert: None,
+            log: None,
+            throughput_log: None,
+            read: None,
+            unmap_v4: false,
+            show_multicast: false,
+            avg_window: DEFAULT_AVG_WINDOW,
+            history_seconds: DEFAULT_HISTORY_SECONDS,
+            max_mem: None,
+            oui_file: None,
+            geoip: None,
+            granularity: KeyGranularity::Proto,
+            replay_speed: None,
+            buffered_accounting: false,
+            anonymize: false,
+            ring_files: None,
+            rotate_bytes: None,
+            rotate_every: None,
+            text_limit: DEFAULT_TEXT_LIMIT,
+            channel_capacity: DEFAULT_CHANNEL_CAPACITY,
+        }
+    }
+}
+
+impl Args {
+    pub fn parse() -> Result<Self, String> {
+        let mut args = Args::default();
+        let mut iter = env::args().skip(1);
+
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "-i" | "--interface" => args.interface = Some(value_for(&arg, iter.next())?),
+                "--list" => args.list_interfaces = true,
+                "--filter" => args.filter = Some(value_for(&arg, iter.next())?),
+                "--dlt" => args.dlt = Some(value_for(&arg, iter.next())?),
+                "--key" => {
+                    let value = value_for(&arg, iter.next())?;
+                    args.granularity = KeyGranularity::from_name(&value).ok_or_else(|| {
+                        format!("Invalid key granularity: {} (host|proto|port|flow)", value)
+                    })?;
+                }
+                "--keymap" => args.keymap = Some(value_for(&arg, iter.next())?.into()),
+                "--avg-window" => {
+                    let value = value_for(&arg, iter.next())?;
+                    args.avg_window = value
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|secs| *secs > 0)
+                        .ok_or_else(|| format!("Invalid averaging window: {}", value))?;
+                }
+                "--history-seconds" => {
+                    let value = value_for(&arg, iter.next())?;
+                    args.history_seconds = value
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|secs| *secs > 0)
+                        .ok_or_else(|| format!("Invalid history length: {}", value))?;
+                }
+                "--anonymize" => args.anonymize = true,
+                "--buffered-accounting" => args.buffered_accounting = true,
+                "--dedup" => args.dedup = true,
+                "--fps" => {
+                    let value = value_for(&arg, iter.next())?;
+                    let fps = value
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|fps| *fps > 0)
+                        .ok_or_else(|| format!("Invalid frame rate: {}", value))?;
+                    args.fps = Some(fps);
+                }
+                "--headless" => args.headless = true,
+                "--log" => args.log = Some(value_for(&arg, iter.next())?.into()),
+                "--max-mem" => {
+                    let value = value_for(&arg, iter.next())?;
+                    let budget = value
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|mb| *mb > 0)
+                        .and_then(|mb| mb.checked_mul(1 << 20))
+                        .ok_or_else(|| format!("Invalid memory budget: {}", value))?;
+                    args.max_mem = Some(budget);
+                }
+                "--new-conn-alert" => {
+                    let value = value_for(&arg, iter.next())?;
+                    let rate = value
+                        .parse::<u64>()
+                        .map_err(|_| format!("Invalid rate: {}", value))?;
+                    args.new_conn_alert = Some(rate);
+                }
+                "--no-confirm" => args.no_confirm = true,
+                "--oui-file" => args.oui_file = Some(value_for(&arg, iter.next())?.into()),
+                "--geoip" => args.geoip = Some(value_for(&arg, iter.next())?.into()),
+                "--text-limit" => {
+                    let value = value_for(&arg, iter.next())?;
+                    args.text_limit = value
+                        .parse::<usize>()
+                        .map_err(|_| format!("Invalid text limit: {}", value))?;
+                }
+                "--throughput-log" => {
+                    args.throughput_log = Some(value_for(&arg, iter.next())?.into())
+                }
+                "--read" => args.read = Some(value_for(&arg, iter.next())?.into()),
+                "--unmap-v4" => args.unmap_v4 = true,
+                "--show-multicast" => args.show_multicast = true,
+                "--replay-speed" => {
+                    let value = value_for(&arg, iter.next())?;
+                    args.replay_speed = match value.as_str() {
+                        "max" => None,
+                        "realtime" => Some(1.0),
+                        factor => Some(
+                            factor
+                                .parse::<f64>()
+                                .ok()
+                                .filter(|x| x.is_finite() && *x > 0.0)
+                                .ok_or_else(|| format!("Invalid replay speed: {}", value))?,
+                        ),
+                    };
+                }
+                "--ring-files" => {
+                    let value = value_for(&arg, iter.next())?;
+                    let count = value
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|n| *n > 0)
+                        .ok_or_else(|| format!("Invalid ring file count: {}", value))?;
+                    args.ring_files = Some(count);
+                }
+                "--rotate-mb" => {
+                    let value = value_for(&arg, iter.next())?;
+                    let bytes = value
+                        .parse::<u64>()
+                        .ok()
+                        .filter(|mb| *mb > 0)
+                        .and_then(|mb| mb.checked_mul(1024 * 1024))
+                        .ok_or_else(|| format!("Invalid rotation size: {}", value))?;
+                    args.rotate_bytes = Some(bytes);
+                }
+                "--rotate-minutes" => {
+                    let value = value_for(&arg, iter.next())?;
+                    let secs = value
+                        .parse::<u64>()
+                        .ok()
+                        .filter(|minutes| *minutes > 0)
+                        .and_then(|minutes| minutes.checked_mul(60))
+                        .ok_or_else(|| format!("Invalid rotation interval: {}", value))?;
+                    args.rotate_every = Some(Duration::from_secs(secs));
+                }
+                "--retain" => {
+                    args.retain_bytes.get_or_insert(DEFAULT_RETAIN_BYTES);
+                }
+                "--retain-bytes" => {
+                    args.retain_bytes = Some(parse_size(&value_for(&arg, iter.next())?)?)
+                }
+                "--channel-capacity" => {
+                    let value = value_for(&arg, iter.next())?;
+                    args.channel_capacity = value
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|n| *n > 0)
+                        .ok_or_else(|| format!("Invalid channel capacity: {}", value))?;
+                }
+                "--chart-ceiling" => {
+                    args.chart_ceiling = parse_size(&value_for(&arg, iter.next())?)? as u64
+                }
+                "--diff" => {
+                    let a = value_for(&arg, iter.next())?;
+                    let b = value_for(&arg, iter.next())?;
+                    args.diff = Some((a.into(), b.into()));
+                }
+                "--duration" => {
+                    let value = value_for(&arg, iter.next())?;
+                    let secs = value
+                        .parse::<u64>()
+                        .map_err(|_| format!("Invalid duration: {}", value))?;
+                    args.duration = Some(Duration::from_secs(secs));
+                }
+                other => return Err(format!("Unknown argument: {}", other)),
+            }
+        }
+
+        Ok(args)
+    }
+}
+
+fn value_for(flag: &str, value: Option<String>) -> Result<String, String> {
+    value.ok_or_else(|| format!("Missing value for {}", flag))
+}
+
+/// Parses a byte count with an optional K/M/G suffix (powers of 1024).
+fn parse_size(value: &str) -> Result<usize, String> {
+    let upper = value.trim().to_uppercase();
+    let number = upper.strip_suffix('B').unwrap_or(&upper);
+    let (digits, multiplier) = if let Some(d) = number.strip_suffix('K') {
+        (d, 1 << 10)
+    } else if let Some(d) = number.strip_suffix('M') {
+        (d, 1 << 20)
+    } else if let Some(d) = number.strip_suffix('G') {
+        (d, 1 << 30)
+    } else {
+        (number, 1)
+    };
+    digits
+        .trim()
+        .parse::<usize>()
+        .ok()
+        .and_then(|n| n.checked_mul(multiplier))
+        .ok_or_else(|| format!("Invalid size: {}", value))
+}
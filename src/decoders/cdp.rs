@@ -0,0 +1,99 @@
+use super::DecodedInfo;
+
+/// LLC/SNAP header CDP rides on: DSAP/SSAP 0xAA, UI, Cisco OUI, PID 0x2000.
+const CDP_SNAP: [u8; 8] = [0xAA, 0xAA, 0x03, 0x00, 0x00, 0x0C, 0x20, 0x00];
+
+/// True for an 802.3 (length-field) payload carrying CDP.
+pub fn is_cdp(ether_type: u16, payload: &[u8]) -> bool {
+    ether_type < 0x0600 && payload.starts_with(&CDP_SNAP)
+}
+
+/// Walks the CDP TLVs (16-bit type, 16-bit length including the header).
+pub fn decode(payload: &[u8]) -> Option<DecodedInfo> {
+    let cdp = payload.get(CDP_SNAP.len()..)?;
+    // Version (1), TTL (1), checksum (2)
+    if cdp.len() < 4 {
+        return None;
+    }
+    let mut details = format!("Type:      CDP v{}\nTTL:       {}s\n", cdp[0], cdp[1]);
+    let mut device_id = None;
+    let mut port_id = None;
+
+    let mut tlvs = &cdp[4..];
+    while tlvs.len() >= 4 {
+        let tlv_type = u16::from_be_bytes([tlvs[0], tlvs[1]]);
+        let len = u16::from_be_bytes([tlvs[2], tlvs[3]]) as usize;
+        if len < 4 {
+            break;
+        }
+        let value = tlvs.get(4..len)?;
+        tlvs = &tlvs[len..];
+
+        match tlv_type {
+            0x0001 => {
+                details.push_str(&format!("Device:    {}\n", text(value)));
+                device_id = Some(text(value));
+            }
+            0x0002 => {
+                for addr in addresses(value) {
+                    details.push_str(&format!("Address:   {}\n", addr));
+                }
+            }
+            0x0003 => {
+                details.push_str(&format!("Port ID:   {}\n", text(value)));
+                port_id = Some(text(value));
+            }
+            0x0004 if value.len() >= 4 => details.push_str(&format!(
+                "Caps:      0x{:08X}\n",
+                u32::from_be_bytes([value[0], value[1], value[2], value[3]])
+            )),
+            0x0005 => {
+                let first_line = text(value).lines().next().unwrap_or_default().to_string();
+                details.push_str(&format!("Software:  {}\n", first_line));
+            }
+            0x0006 => details.push_str(&format!("Platform:  {}\n", text(value))),
+            0x000A if value.len() >= 2 => details.push_str(&format!(
+                "VLAN:      {}\n",
+                u16::from_be_bytes([value[0], value[1]])
+            )),
+            0x000B if !value.is_empty() => details.push_str(&format!(
+                "Duplex:    {}\n",
+                if value[0] == 1 { "full" } else { "half" }
+            )),
+            other => details.push_str(&format!("TLV 0x{:04X} {} bytes\n", other, value.len())),
+        }
+    }
+
+    let label = match (device_id, port_id) {
+        (Some(device), Some(port)) => format!("CDP {} {}", device, port),
+        (Some(device), None) => format!("CDP {}", device),
+        (None, Some(port)) => format!("CDP {}", port),
+        (None, None) => "CDP".into(),
+    };
+    Some(DecodedInfo { label, details })
+}
+
+/// IPv4 entries of an Addresses TLV; other protocols are skipped.
+fn addresses(value: &[u8]) -> Vec<String> {
+    let mut found = Vec::new();
+    let mut rest = value.get(4..).unwrap_or_default();
+    while rest.len() >= 2 {
+        let proto_len = rest[1] as usize;
+        let Some(len_bytes) = rest.get(2 + proto_len..4 + proto_len) else {
+            break;
+        };
+        let addr_len = u16::from_be_bytes([len_bytes[0], len_bytes[1]]) as usize;
+        let Some(addr) = rest.get(4 + proto_len..4 + proto_len + addr_len) else {
+            break;
+        };
+        if rest[0] == 1 && rest[2..2 + proto_len] == [0xCC] && addr_len == 4 {
+            found.push(format!("{}.{}.{}.{}", addr[0], addr[1], addr[2], addr[3]));
+        }
+        rest = &rest[4 + proto_len + addr_len..];
+    }
+    found
+}
+
+fn text(value: &[u8]) -> String {
+    String::from_utf8_lossy(value).trim().to_string()
+}
@@ -0,0 +1,201 @@
+use super::{DecodedInfo, ProtocolDecoder, uses_port};
+use etherparse::TransportSlice;
+use std::net::Ipv4Addr;
+
+/// DHCP (BOOTP with the DHCP magic cookie) on UDP 67/68.
+pub struct DhcpDecoder;
+
+/// Fixed BOOTP header; the magic cookie and options follow.
+const BOOTP_HEADER: usize = 236;
+const MAGIC_COOKIE: [u8; 4] = [99, 130, 83, 99];
+
+const OPT_SUBNET_MASK: u8 = 1;
+const OPT_ROUTER: u8 = 3;
+const OPT_DNS: u8 = 6;
+const OPT_HOSTNAME: u8 = 12;
+const OPT_REQUESTED_IP: u8 = 50;
+const OPT_LEASE_TIME: u8 = 51;
+const OPT_MESSAGE_TYPE: u8 = 53;
+const OPT_SERVER_ID: u8 = 54;
+
+/// The parts of a DHCP message the summary and details show.
+struct DhcpMessage<'a> {
+    xid: u32,
+    ciaddr: Ipv4Addr,
+    yiaddr: Ipv4Addr,
+    chaddr: Option<&'a [u8]>,
+    /// (code, value) in the order they appear
+    options: Vec<(u8, &'a [u8])>,
+}
+
+impl<'a> DhcpMessage<'a> {
+    fn parse(payload: &'a [u8]) -> Option<Self> {
+        if payload.get(BOOTP_HEADER..BOOTP_HEADER + 4)? != MAGIC_COOKIE {
+            return None;
+        }
+        let address = |at: usize| {
+            Ipv4Addr::new(
+                payload[at],
+                payload[at + 1],
+                payload[at + 2],
+                payload[at + 3],
+            )
+        };
+        let hlen = payload[2] as usize;
+        Some(Self {
+            xid: u32::from_be_bytes([payload[4], payload[5], payload[6], payload[7]]),
+            ciaddr: address(12),
+            yiaddr: address(16),
+            chaddr: (1..=16).contains(&hlen).then(|| &payload[28..28 + hlen]),
+            options: options(&payload[BOOTP_HEADER + 4..]),
+        })
+    }
+
+    fn option(&self, code: u8) -> Option<&'a [u8]> {
+        self.options
+            .iter()
+            .find(|(c, _)| *c == code)
+            .map(|(_, value)| *value)
+    }
+
+    fn message_type(&self) -> Option<u8> {
+        self.option(OPT_MESSAGE_TYPE)?.first().copied()
+    }
+
+    /// The address the message is about: what the server hands out, or what the
+    /// client asks for or already holds.
+    fn subject(&self) -> Option<Ipv4Addr> {
+        let requested = self
+            .option(OPT_REQUESTED_IP)
+            .and_then(|v| addresses(v).into_iter().next());
+        [Some(self.yiaddr), requested, Some(self.ciaddr)]
+            .into_iter()
+            .flatten()
+            .find(|addr| !addr.is_unspecified())
+    }
+}
+
+impl ProtocolDecoder for DhcpDecoder {
+    fn decode(&self, transport: &TransportSlice, payload: &[u8]) -> Option<DecodedInfo> {
+        if !is_dhcp_segment(transport) {
+            return None;
+        }
+        let message = DhcpMessage::parse(payload)?;
+
+        let mut details = format!(
+            "Type:      DHCP {}\nXID:       0x{:08X}\n",
+            message_type_name(message.message_type()),
+            message.xid
+        );
+        if let Some(mac) = message.chaddr {
+            let mac: Vec<String> = mac.iter().map(|b| format!("{:02x}", b)).collect();
+            details.push_str(&format!("Client:    {}\n", mac.join(":")));
+        }
+        if !message.ciaddr.is_unspecified() {
+            details.push_str(&format!("Client IP: {}\n", message.ciaddr));
+        }
+        if !message.yiaddr.is_unspecified() {
+            details.push_str(&format!("Your IP:   {}\n", message.yiaddr));
+        }
+        for (code, value) in &message.options {
+            let line = match *code {
+                OPT_REQUESTED_IP => format!("Requested: {}", join(&addresses(value))),
+                OPT_SERVER_ID => format!("Server:    {}", join(&addresses(value))),
+                OPT_LEASE_TIME if value.len() == 4 => {
+                    let secs = u32::from_be_bytes([value[0], value[1], value[2], value[3]]);
+                    format!("Lease:     {}", lease(secs))
+                }
+                OPT_SUBNET_MASK => format!("Mask:      {}", join(&addresses(value))),
+                OPT_ROUTER => format!("Router:    {}", join(&addresses(value))),
+                OPT_DNS => format!("DNS:       {}", join(&addresses(value))),
+                OPT_HOSTNAME => format!("Hostname:  {}", String::from_utf8_lossy(value)),
+                _ => continue,
+            };
+            details.push_str(&line);
+            details.push('\n');
+        }
+
+        Some(DecodedInfo {
+            label: "DHCP".into(),
+            details,
+        })
+    }
+}
+
+/// "ACK 192.168.1.77" for the feed and summary line.
+pub fn summary(transport: &TransportSlice, payload: &[u8]) -> Option<String> {
+    if !is_dhcp_segment(transport) {
+        return None;
+    }
+    let message = DhcpMessage::parse(payload)?;
+    let name = message_type_name(message.message_type());
+    Some(match message.subject() {
+        Some(addr) => format!("{} {}", name, addr),
+        None => name.to_string(),
+    })
+}
+
+fn is_dhcp_segment(transport: &TransportSlice) -> bool {
+    matches!(transport, TransportSlice::Udp(_))
+        && (uses_port(transport, 67) || uses_port(transport, 68))
+}
+
+fn message_type_name(message_type: Option<u8>) -> &'static str {
+    match message_type {
+        Some(1) => "DISCOVER",
+        Some(2) => "OFFER",
+        Some(3) => "REQUEST",
+        Some(4) => "DECLINE",
+        Some(5) => "ACK",
+        Some(6) => "NAK",
+        Some(7) => "RELEASE",
+        Some(8) => "INFORM",
+        // Plain BOOTP, or a type this decoder doesn't know
+        _ => "BOOTP",
+    }
+}
+
+/// (code, value) pairs up to the end option. A truncated option ends the list.
+fn options(mut data: &[u8]) -> Vec<(u8, &[u8])> {
+    let mut out = Vec::new();
+    while let Some((&code, rest)) = data.split_first() {
+        match code {
+            0 => data = rest,
+            255 => break,
+            _ => {
+                let Some((&len, rest)) = rest.split_first() else {
+                    break;
+                };
+                let Some(value) = rest.get(..len as usize) else {
+                    break;
+                };
+                out.push((code, value));
+                data = &rest[len as usize..];
+            }
+        }
+    }
+    out
+}
+
+fn addresses(value: &[u8]) -> Vec<Ipv4Addr> {
+    value
+        .chunks_exact(4)
+        .map(|a| Ipv4Addr::new(a[0], a[1], a[2], a[3]))
+        .collect()
+}
+
+fn join(addresses: &[Ipv4Addr]) -> String {
+    addresses
+        .iter()
+        .map(Ipv4Addr::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// "86400 s (1d 0h)"; an all-ones lease means it never expires.
+fn lease(secs: u32) -> String {
+    if secs == u32::MAX {
+        return "infinite".into();
+    }
+    format!("{} s ({}d {}h)", secs, secs / 86_400, secs / 3600 % 24)
+}
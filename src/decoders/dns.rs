@@ -0,0 +1,268 @@
+use super::{DecodedInfo, ProtocolDecoder, uses_port};
+use etherparse::TransportSlice;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+/// Resource records listed in the details before the rest are summarized as a count.
+const MAX_RECORDS_SHOWN: usize = 16;
+
+pub struct DnsDecoder;
+
+/// The parts of a DNS message needed to pair a query with its response.
+#[derive(Debug, Clone)]
+pub struct DnsMessage {
+    pub id: u16,
+    pub is_response: bool,
+    /// First question's name, or the first answer's for mDNS announcements that carry
+    /// no question; `None` if both sections are missing or malformed
+    pub name: Option<String>,
+    /// QTYPE (or answer TYPE) that goes with `name`
+    pub qtype: Option<u16>,
+}
+
+impl DnsMessage {
+    /// "A api.github.com", for the feed and summary line.
+    pub fn question(&self) -> Option<String> {
+        let name = self.name.as_ref()?;
+        Some(match self.qtype {
+            Some(qtype) => format!("{} {}", type_name(qtype), name),
+            None => name.clone(),
+        })
+    }
+}
+
+/// Header and first question of a DNS, mDNS or LLMNR segment, for cross-packet
+/// tracking and the feed.
+pub fn dns_message(transport: &TransportSlice, payload: &[u8]) -> Option<DnsMessage> {
+    let (_, message) = framed_message(transport, payload)?;
+    if message.len() < 12 {
+        return None;
+    }
+    // Questions and answers both start with name then type, so an unsolicited
+    // answer reads the same way
+    let question = match (word_at(message, 4), word_at(message, 6)) {
+        (0, 0) => None,
+        _ => read_name(message, 12),
+    };
+    Some(DnsMessage {
+        id: word_at(message, 0),
+        is_response: message[2] & 0x80 != 0,
+        qtype: question
+            .as_ref()
+            .and_then(|(_, end)| message.get(*end..*end + 2))
+            .map(|t| word_at(t, 0)),
+        name: question.map(|(name, _)| name),
+    })
+}
+
+/// Label and the DNS message itself, minus TCP's length prefix. mDNS and LLMNR use
+/// the same message format for local-link name resolution.
+fn framed_message<'a>(
+    transport: &TransportSlice,
+    payload: &'a [u8],
+) -> Option<(&'static str, &'a [u8])> {
+    match transport {
+        TransportSlice::Udp(_) if uses_port(transport, 53) => Some(("DNS", payload)),
+        TransportSlice::Udp(_) if uses_port(transport, 5353) => Some(("mDNS", payload)),
+        TransportSlice::Udp(_) if uses_port(transport, 5355) => Some(("LLMNR", payload)),
+        // Over TCP each message carries a 2-byte length prefix. Bare ACKs have no
+        // payload and stay plain TCP.
+        TransportSlice::Tcp(_) if uses_port(transport, 53) && payload.len() >= 2 => {
+            Some(("DNS/TCP", &payload[2..]))
+        }
+        _ => None,
+    }
+}
+
+impl ProtocolDecoder for DnsDecoder {
+    fn decode(&self, transport: &TransportSlice, payload: &[u8]) -> Option<DecodedInfo> {
+        let (label, message) = framed_message(transport, payload)?;
+        // Fixed 12-byte header: id, flags, then four section counts
+        if message.len() < 12 {
+            return Some(DecodedInfo {
+                label: label.into(),
+                details: format!(
+                    "Type:      {} (truncated header)\n",
+                    label.trim_end_matches("/TCP")
+                ),
+            });
+        }
+
+        let word = |i: usize| u16::from_be_bytes([message[i], message[i + 1]]);
+        let flags = word(2);
+        let is_response = flags & 0x8000 != 0;
+
+        let mut details = format!(
+            "Type:      {} {}\nID:        0x{:04X}\nOpcode:    {}\nRcode:     {}\nQuestions: {}\nAnswers:   {}\n",
+            label.trim_end_matches("/TCP"),
+            if is_response { "Response" } else { "Query" },
+            word(0),
+            (flags >> 11) & 0xF,
+            flags & 0xF,
+            word(4),
+            word(6)
+        );
+        if label == "DNS/TCP" {
+            details.push_str(&format!("Length:    {}\n", word_at(payload, 0)));
+        }
+        details.push_str(&records(message, word(4), word(6)));
+
+        // A zone transfer asks for the whole zone; worth noticing when it's unexpected
+        let transfer = match question_type(message) {
+            Some(252) if word(4) > 0 => Some("AXFR"),
+            Some(251) if word(4) > 0 => Some("IXFR"),
+            _ => None,
+        };
+        let label = match transfer {
+            Some(kind) => {
+                details.push_str(&format!("Transfer:  {} (zone transfer)\n", kind));
+                format!("{} {}", label, kind)
+            }
+            None => label.to_string(),
+        };
+
+        Some(DecodedInfo { label, details })
+    }
+}
+
+fn word_at(data: &[u8], i: usize) -> u16 {
+    u16::from_be_bytes([data[i], data[i + 1]])
+}
+
+/// One line per question and answer record. Parsing stops quietly at the first record
+/// that runs past the end of the message.
+fn records(message: &[u8], questions: u16, answers: u16) -> String {
+    let mut out = String::new();
+    let mut pos = 12;
+    for i in 0..questions as usize {
+        let Some((name, end)) = read_name(message, pos) else {
+            return out;
+        };
+        let Some(qtype) = message.get(end..end + 2).map(|t| word_at(t, 0)) else {
+            return out;
+        };
+        if i < MAX_RECORDS_SHOWN {
+            out.push_str(&format!("Question:  {} {}\n", type_name(qtype), name));
+        }
+        pos = end + 4;
+    }
+    for i in 0..answers as usize {
+        if i == MAX_RECORDS_SHOWN {
+            out.push_str(&format!(
+                "           … {} more answers\n",
+                answers as usize - i
+            ));
+            break;
+        }
+        let Some((name, end)) = read_name(message, pos) else {
+            return out;
+        };
+        let Some(fixed) = message.get(end..end + 10) else {
+            return out;
+        };
+        let rtype = word_at(fixed, 0);
+        let ttl = u32::from_be_bytes([fixed[4], fixed[5], fixed[6], fixed[7]]);
+        let rdata_start = end + 10;
+        let rdata_end = rdata_start + word_at(fixed, 8) as usize;
+        let Some(rdata) = message.get(rdata_start..rdata_end) else {
+            return out;
+        };
+        out.push_str(&format!(
+            "Answer:    {} {} {} (TTL {})\n",
+            name,
+            type_name(rtype),
+            rdata_text(message, rtype, rdata, rdata_start),
+            ttl
+        ));
+        pos = rdata_end;
+    }
+    out
+}
+
+/// Record data in presentation form for the common types, a byte count otherwise.
+fn rdata_text(message: &[u8], rtype: u16, rdata: &[u8], start: usize) -> String {
+    let name_at = |offset: usize| {
+        read_name(message, start + offset)
+            .map(|(name, _)| name)
+            .unwrap_or_else(|| "?".into())
+    };
+    match (rtype, rdata.len()) {
+        (1, 4) => Ipv4Addr::new(rdata[0], rdata[1], rdata[2], rdata[3]).to_string(),
+        (28, 16) => {
+            let bytes: [u8; 16] = rdata.try_into().unwrap_or_default();
+            Ipv6Addr::from(bytes).to_string()
+        }
+        // NS, CNAME, PTR
+        (2 | 5 | 12, _) => name_at(0),
+        (15, 3..) => format!("{} {}", word_at(rdata, 0), name_at(2)),
+        (16, _) => {
+            // Length-prefixed character strings
+            let mut text = Vec::new();
+            let mut rest = rdata;
+            while let Some((&len, tail)) = rest.split_first() {
+                let len = (len as usize).min(tail.len());
+                text.push(format!("\"{}\"", String::from_utf8_lossy(&tail[..len])));
+                rest = &tail[len..];
+            }
+            text.join(" ")
+        }
+        (_, len) => format!("<{} bytes>", len),
+    }
+}
+
+/// Mnemonic for a record type, or `TYPEnnn` (RFC 3597) for unlisted ones.
+fn type_name(rtype: u16) -> String {
+    match rtype {
+        1 => "A".into(),
+        2 => "NS".into(),
+        5 => "CNAME".into(),
+        6 => "SOA".into(),
+        12 => "PTR".into(),
+        15 => "MX".into(),
+        16 => "TXT".into(),
+        28 => "AAAA".into(),
+        33 => "SRV".into(),
+        41 => "OPT".into(),
+        64 => "SVCB".into(),
+        65 => "HTTPS".into(),
+        251 => "IXFR".into(),
+        252 => "AXFR".into(),
+        255 => "ANY".into(),
+        other => format!("TYPE{}", other),
+    }
+}
+
+/// QTYPE of the first question, found by walking past its name labels.
+fn question_type(message: &[u8]) -> Option<u16> {
+    let (_, end) = read_name(message, 12)?;
+    message.get(end..end + 2).map(|t| word_at(t, 0))
+}
+
+/// Decodes the name at `start`, following compression pointers. Returns the dotted
+/// name ("." for the root) and the offset just past it in the original position.
+fn read_name(message: &[u8], start: usize) -> Option<(String, usize)> {
+    let mut labels = Vec::new();
+    let mut pos = start;
+    let mut end = None;
+    // Pointers may only go backwards in sane messages; the cap stops loops in hostile ones
+    for _ in 0..128 {
+        let len = *message.get(pos)? as usize;
+        if len == 0 {
+            let name = if labels.is_empty() {
+                ".".to_string()
+            } else {
+                labels.join(".")
+            };
+            return Some((name, end.unwrap_or(pos + 1)));
+        }
+        if len & 0xC0 == 0xC0 {
+            let target = (len & 0x3F) << 8 | *message.get(pos + 1)? as usize;
+            end.get_or_insert(pos + 2);
+            pos = target;
+            continue;
+        }
+        let label = message.get(pos + 1..pos + 1 + len)?;
+        labels.push(String::from_utf8_lossy(label).into_owned());
+        pos += 1 + len;
+    }
+    None
+}
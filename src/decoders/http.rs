@@ -0,0 +1,97 @@
+use super::{DecodedInfo, ProtocolDecoder};
+use etherparse::TransportSlice;
+
+pub struct HttpDecoder;
+
+const METHODS: &[&str] = &[
+    "GET ", "POST ", "PUT ", "DELETE ", "HEAD ", "OPTIONS ", "PATCH ", "CONNECT ", "TRACE ",
+];
+
+/// Longest first line we bother showing; anything past this is almost certainly not HTTP.
+const MAX_LINE: usize = 256;
+
+/// Bytes of a header block that are parsed; the rest of an oversized one is ignored.
+const MAX_HEADERS: usize = 8192;
+
+impl ProtocolDecoder for HttpDecoder {
+    fn decode(&self, transport: &TransportSlice, payload: &[u8]) -> Option<DecodedInfo> {
+        if !matches!(transport, TransportSlice::Tcp(_)) {
+            return None;
+        }
+        let is_request = is_request(payload);
+        let is_response = payload.starts_with(b"HTTP/1.");
+        if !is_request && !is_response {
+            return None;
+        }
+
+        let head = &payload[..payload.len().min(MAX_LINE)];
+        let first_line = head.split(|&b| b == b'\r' || b == b'\n').next()?;
+
+        let mut details = format!(
+            "Type:      HTTP {}\nLine:      {}\n",
+            if is_request { "Request" } else { "Response" },
+            String::from_utf8_lossy(first_line)
+        );
+        let (block, complete) = header_block(payload);
+        let headers: Vec<&str> = block.lines().skip(1).collect();
+        if !headers.is_empty() {
+            details.push_str("Headers:\n");
+            for header in headers {
+                details.push_str(&format!("  {}\n", header));
+            }
+        }
+        if !complete {
+            details.push_str("  … continues past this segment\n");
+        }
+
+        Some(DecodedInfo {
+            label: "HTTP".into(),
+            details,
+        })
+    }
+}
+
+fn is_request(payload: &[u8]) -> bool {
+    METHODS.iter().any(|m| payload.starts_with(m.as_bytes()))
+}
+
+/// The start line and headers present in this segment, and whether the blank line
+/// ending them was among them. Headers split across segments are not reassembled.
+fn header_block(payload: &[u8]) -> (String, bool) {
+    let head = &payload[..payload.len().min(MAX_HEADERS)];
+    match head.windows(4).position(|w| w == b"\r\n\r\n") {
+        Some(end) => (String::from_utf8_lossy(&head[..end]).into_owned(), true),
+        None => (String::from_utf8_lossy(head).into_owned(), false),
+    }
+}
+
+/// "GET example.com/api/v1/users" from a request's first line and Host header. When
+/// the Host header isn't in this segment the target is shown as sent.
+pub fn request_summary(payload: &[u8]) -> Option<String> {
+    if !is_request(payload) {
+        return None;
+    }
+    let (block, _) = header_block(payload);
+    let mut lines = block.lines();
+    let mut request_line = lines.next()?.split(' ');
+    let method = request_line.next()?;
+    let target = request_line.next().filter(|t| !t.is_empty())?;
+    let host = lines.find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        name.trim()
+            .eq_ignore_ascii_case("host")
+            .then(|| value.trim())
+    });
+
+    // Proxies get the absolute form, which already names the host
+    let absolute = target
+        .strip_prefix("http://")
+        .or_else(|| target.strip_prefix("https://"));
+    let location = match (absolute, host) {
+        (Some(absolute), _) => absolute.to_string(),
+        (None, Some(host)) if target.starts_with('/') => format!("{}{}", host, target),
+        (None, _) => target.to_string(),
+    };
+    let summary = format!("{} {}", method, location);
+    Some(summary.chars().take(MAX_LINE).collect())
+}
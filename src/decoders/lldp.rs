@@ -0,0 +1,81 @@
+use super::DecodedInfo;
+use crate::capture::format_mac;
+
+pub const ETHERTYPE_LLDP: u16 = 0x88CC;
+
+/// Walks the LLDPDU TLVs (7-bit type, 9-bit length) up to the End TLV.
+pub fn decode(mut tlvs: &[u8]) -> Option<DecodedInfo> {
+    let mut details = String::from("Type:      LLDP\n");
+    let mut system_name = None;
+    let mut port_id = None;
+
+    while tlvs.len() >= 2 {
+        let header = u16::from_be_bytes([tlvs[0], tlvs[1]]);
+        let (tlv_type, len) = ((header >> 9) as u8, (header & 0x1FF) as usize);
+        let value = tlvs.get(2..2 + len)?;
+        tlvs = &tlvs[2 + len..];
+
+        match tlv_type {
+            0 => break,
+            1 => details.push_str(&format!("Chassis:   {}\n", subtyped_id(value, 4))),
+            2 => {
+                let id = subtyped_id(value, 3);
+                details.push_str(&format!("Port ID:   {}\n", id));
+                port_id = Some(id);
+            }
+            3 if len >= 2 => details.push_str(&format!(
+                "TTL:       {}s\n",
+                u16::from_be_bytes([value[0], value[1]])
+            )),
+            4 => details.push_str(&format!("Port Desc: {}\n", text(value))),
+            5 => {
+                details.push_str(&format!("System:    {}\n", text(value)));
+                system_name = Some(text(value));
+            }
+            6 => details.push_str(&format!("Sys Desc:  {}\n", text(value))),
+            7 if len >= 4 => details.push_str(&format!(
+                "Caps:      0x{:04X} (enabled 0x{:04X})\n",
+                u16::from_be_bytes([value[0], value[1]]),
+                u16::from_be_bytes([value[2], value[3]])
+            )),
+            8 => details.push_str(&format!("Mgmt Addr: {}\n", management_address(value))),
+            // IEEE 802.1 organizationally specific: subtype 1 is the Port VLAN ID
+            127 if len >= 6 && value[..3] == [0x00, 0x80, 0xC2] && value[3] == 1 => details
+                .push_str(&format!(
+                    "VLAN:      {}\n",
+                    u16::from_be_bytes([value[4], value[5]])
+                )),
+            other => details.push_str(&format!("TLV {:<5} {} bytes\n", other, len)),
+        }
+    }
+
+    let label = match (system_name, port_id) {
+        (Some(name), Some(port)) => format!("LLDP {} {}", name, port),
+        (Some(name), None) => format!("LLDP {}", name),
+        (None, Some(port)) => format!("LLDP {}", port),
+        (None, None) => "LLDP".into(),
+    };
+    Some(DecodedInfo { label, details })
+}
+
+/// Chassis/Port ID values start with a subtype; `mac_subtype` is the one holding a MAC.
+fn subtyped_id(value: &[u8], mac_subtype: u8) -> String {
+    match value.split_first() {
+        Some((&subtype, rest)) if subtype == mac_subtype && rest.len() == 6 => format_mac(rest),
+        Some((_, rest)) => text(rest),
+        None => String::new(),
+    }
+}
+
+fn management_address(value: &[u8]) -> String {
+    // Address string length (includes the family byte), family, address
+    match value {
+        [5, 1, a, b, c, d, ..] => format!("{}.{}.{}.{}", a, b, c, d),
+        [7, 6, rest @ ..] if rest.len() >= 6 => format_mac(&rest[..6]),
+        _ => format!("{} bytes", value.len()),
+    }
+}
+
+fn text(value: &[u8]) -> String {
+    String::from_utf8_lossy(value).trim().to_string()
+}
@@ -0,0 +1,160 @@
+//! Application-layer decoders.
+//!
+//! Each protocol lives in its own module and implements [`ProtocolDecoder`]. The
+//! registry asks them in priority order; the first one that claims a packet supplies
+//! its label and the APPLICATION LAYER section of the details.
+//!
+//! Link-layer protocols that never reach IP (LLDP, CDP) go through
+//! [`decode_link_layer`] instead.
+
+mod cdp;
+mod dhcp;
+mod dns;
+mod http;
+mod lldp;
+mod smb;
+mod syslog;
+mod tls;
+
+pub use dns::DnsMessage;
+use etherparse::{EtherPayloadSlice, TransportSlice};
+use std::sync::OnceLock;
+pub use tls::TlsHello;
+
+pub struct DecodedInfo {
+    /// Short label used in the summary and the connections key (e.g. "DNS")
+    pub label: String,
+    /// Multi-line breakdown for the inspector pane
+    pub details: String,
+}
+
+pub trait ProtocolDecoder: Send + Sync {
+    fn decode(&self, transport: &TransportSlice, payload: &[u8]) -> Option<DecodedInfo>;
+}
+
+/// Built-in decoders, most specific first.
+fn registry() -> &'static [Box<dyn ProtocolDecoder>] {
+    static REGISTRY: OnceLock<Vec<Box<dyn ProtocolDecoder>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        vec![
+            Box::new(dns::DnsDecoder),
+            Box::new(dhcp::DhcpDecoder),
+            Box::new(tls::TlsDecoder),
+            Box::new(http::HttpDecoder),
+            Box::new(smb::SmbDecoder),
+            Box::new(syslog::SyslogDecoder),
+        ]
+    })
+}
+
+/// Runs the registry over a transport segment and returns the first match.
+pub fn decode_application(transport: &TransportSlice) -> Option<DecodedInfo> {
+    let payload = transport_payload(transport);
+    registry()
+        .iter()
+        .find_map(|decoder| decoder.decode(transport, payload))
+}
+
+/// DNS header and first question, when the segment is DNS at all.
+pub fn dns_message(transport: &TransportSlice) -> Option<DnsMessage> {
+    dns::dns_message(transport, transport_payload(transport))
+}
+
+/// Version (and server name) when the segment starts with a TLS hello.
+pub fn tls_hello(transport: &TransportSlice) -> Option<TlsHello> {
+    match transport {
+        TransportSlice::Tcp(tcp) => tls::parse_hello(tcp.payload()),
+        _ => None,
+    }
+}
+
+/// "GET example.com/path" when the segment starts an HTTP request.
+pub fn http_request(transport: &TransportSlice) -> Option<String> {
+    match transport {
+        TransportSlice::Tcp(tcp) => http::request_summary(tcp.payload()),
+        _ => None,
+    }
+}
+
+/// "ACK 192.168.1.77" when the segment is a DHCP message.
+pub fn dhcp_summary(transport: &TransportSlice) -> Option<String> {
+    dhcp::summary(transport, transport_payload(transport))
+}
+
+/// "CREATE" or "NEGOTIATE SMB 3.1.1" when the segment carries an SMB command.
+pub fn smb_command(transport: &TransportSlice) -> Option<String> {
+    smb::summary(transport, transport_payload(transport))
+}
+
+/// `local0.info "message..."` when the segment is a syslog message.
+pub fn syslog_summary(transport: &TransportSlice) -> Option<String> {
+    syslog::summary(transport, transport_payload(transport))
+}
+
+/// Decodes non-IP payloads by ethertype (or LLC/SNAP header for 802.3 frames).
+pub fn decode_link_layer(payload: &EtherPayloadSlice) -> Option<DecodedInfo> {
+    let ether_type = payload.ether_type.0;
+    if ether_type == lldp::ETHERTYPE_LLDP {
+        lldp::decode(payload.payload)
+    } else if cdp::is_cdp(ether_type, payload.payload) {
+        cdp::decode(payload.payload)
+    } else {
+        None
+    }
+}
+
+/// Source and destination ports for TCP/UDP, `None` for everything else.
+pub fn ports(transport: &TransportSlice) -> Option<(u16, u16)> {
+    match transport {
+        TransportSlice::Tcp(tcp) => Some((tcp.source_port(), tcp.destination_port())),
+        TransportSlice::Udp(udp) => Some((udp.source_port(), udp.destination_port())),
+        _ => None,
+    }
+}
+
+/// Ports of line-oriented plaintext protocols: FTP, SMTP, HTTP, POP3, IMAP,
+/// syslog, submission, HTTP-alt and SIP.
+const TEXT_PORTS: &[u16] = &[21, 25, 80, 110, 143, 514, 587, 8080, 5060];
+
+/// The payload of a plaintext-protocol segment as readable lines, for the inspector
+/// to show instead of hex. `None` for other protocols, empty segments, and payloads
+/// that turn out to be mostly binary (TLS on a text port, compressed bodies).
+pub fn text_payload(transport: &TransportSlice) -> Option<String> {
+    let payload = transport_payload(transport);
+    if payload.is_empty() || !TEXT_PORTS.iter().any(|port| uses_port(transport, *port)) {
+        return None;
+    }
+    let printable = payload
+        .iter()
+        .filter(|&&b| b.is_ascii_graphic() || b.is_ascii_whitespace() || b >= 0x80)
+        .count();
+    if printable * 10 < payload.len() * 9 {
+        return None;
+    }
+    Some(
+        String::from_utf8_lossy(payload)
+            .chars()
+            .filter(|c| *c != '\r')
+            .map(|c| {
+                if c.is_control() && c != '\n' && c != '\t' {
+                    '·'
+                } else {
+                    c
+                }
+            })
+            .collect(),
+    )
+}
+
+fn transport_payload<'a>(transport: &TransportSlice<'a>) -> &'a [u8] {
+    match transport {
+        TransportSlice::Tcp(tcp) => tcp.payload(),
+        TransportSlice::Udp(udp) => udp.payload(),
+        _ => &[],
+    }
+}
+
+/// True when either side of the segment uses `port`.
+fn uses_port(transport: &TransportSlice, port: u16) -> bool {
+    ports(transport).is_some_and(|(src, dst)| src == port || dst == port)
+}
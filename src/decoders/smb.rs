@@ -0,0 +1,190 @@
+use super::{DecodedInfo, ProtocolDecoder, uses_port};
+use etherparse::TransportSlice;
+
+/// SMB over direct TCP (445) or NetBIOS sessions (139), plus the NetBIOS name and
+/// datagram services on UDP 137/138.
+pub struct SmbDecoder;
+
+/// A decoded SMB/NetBIOS segment. The label stays at the protocol (it is part of the
+/// connection key, and a session runs many commands); the command goes in `command`.
+struct SmbSegment {
+    decoded: DecodedInfo,
+    /// "CREATE", "NEGOTIATE SMB 3.1.1", for the feed summary
+    command: Option<String>,
+}
+
+impl SmbSegment {
+    fn plain(label: &str, details: String) -> Self {
+        Self {
+            decoded: DecodedInfo {
+                label: label.into(),
+                details,
+            },
+            command: None,
+        }
+    }
+}
+
+impl ProtocolDecoder for SmbDecoder {
+    fn decode(&self, transport: &TransportSlice, payload: &[u8]) -> Option<DecodedInfo> {
+        decode(transport, payload).map(|segment| segment.decoded)
+    }
+}
+
+/// The SMB command (and negotiated dialect) of the segment, for the feed summary.
+pub fn summary(transport: &TransportSlice, payload: &[u8]) -> Option<String> {
+    decode(transport, payload)?.command
+}
+
+fn decode(transport: &TransportSlice, payload: &[u8]) -> Option<SmbSegment> {
+    match transport {
+        TransportSlice::Udp(_) if uses_port(transport, 137) => Some(SmbSegment::plain(
+            "NetBIOS-NS",
+            "Type:      NetBIOS Name Service\n".into(),
+        )),
+        TransportSlice::Udp(_) if uses_port(transport, 138) => Some(SmbSegment::plain(
+            "NetBIOS-DGM",
+            "Type:      NetBIOS Datagram Service\n".into(),
+        )),
+        TransportSlice::Tcp(_) if uses_port(transport, 445) || uses_port(transport, 139) => {
+            decode_session(payload, uses_port(transport, 139))
+        }
+        _ => None,
+    }
+}
+
+/// Both ports frame SMB in a 4-byte NetBIOS session header (type, 24-bit length).
+fn decode_session(payload: &[u8], netbios: bool) -> Option<SmbSegment> {
+    if payload.len() < 4 {
+        return None;
+    }
+    let smb = &payload[4..];
+    match smb.get(..4) {
+        Some(b"\xFFSMB") => Some(smb1(smb)),
+        Some(b"\xFESMB") => Some(smb2(smb)),
+        Some(b"\xFDSMB") => Some(SmbSegment::plain(
+            "SMB3 encrypted",
+            "Type:      SMB3 Transform (encrypted)\n".into(),
+        )),
+        _ if netbios => Some(SmbSegment::plain(
+            "NetBIOS",
+            format!(
+                "Type:      NetBIOS Session\nMessage:   {}\n",
+                session_message(payload[0])
+            ),
+        )),
+        _ => None,
+    }
+}
+
+fn smb1(smb: &[u8]) -> SmbSegment {
+    let Some(&command) = smb.get(4) else {
+        return SmbSegment::plain("SMB1", "Type:      SMB1 (truncated header)\n".into());
+    };
+    // Flags byte, bit 7: reply
+    let is_reply = smb.get(9).is_some_and(|flags| flags & 0x80 != 0);
+    let name = match command {
+        0x04 => "CLOSE",
+        0x25 => "TRANS",
+        0x2E => "READ_ANDX",
+        0x2F => "WRITE_ANDX",
+        0x32 => "TRANS2",
+        0x71 => "TREE_DISCONNECT",
+        0x72 => "NEGOTIATE",
+        0x73 => "SESSION_SETUP_ANDX",
+        0x74 => "LOGOFF_ANDX",
+        0x75 => "TREE_CONNECT_ANDX",
+        0xA2 => "NT_CREATE_ANDX",
+        _ => "OTHER",
+    };
+    SmbSegment {
+        decoded: DecodedInfo {
+            label: "SMB1".into(),
+            details: format!(
+                "Type:      SMB1 {}\nCommand:   {} (0x{:02X})\n",
+                if is_reply { "Response" } else { "Request" },
+                name,
+                command
+            ),
+        },
+        command: Some(name.into()),
+    }
+}
+
+fn smb2(smb: &[u8]) -> SmbSegment {
+    // Fixed 64-byte header; the command and flags are little-endian
+    if smb.len() < 64 {
+        return SmbSegment::plain("SMB2", "Type:      SMB2 (truncated header)\n".into());
+    }
+    let command = u16::from_le_bytes([smb[12], smb[13]]);
+    let status = u32::from_le_bytes([smb[8], smb[9], smb[10], smb[11]]);
+    let is_reply = smb[16] & 0x01 != 0;
+    let name = match command {
+        0 => "NEGOTIATE",
+        1 => "SESSION_SETUP",
+        2 => "LOGOFF",
+        3 => "TREE_CONNECT",
+        4 => "TREE_DISCONNECT",
+        5 => "CREATE",
+        6 => "CLOSE",
+        7 => "FLUSH",
+        8 => "READ",
+        9 => "WRITE",
+        10 => "LOCK",
+        11 => "IOCTL",
+        12 => "CANCEL",
+        13 => "ECHO",
+        14 => "QUERY_DIRECTORY",
+        15 => "CHANGE_NOTIFY",
+        16 => "QUERY_INFO",
+        17 => "SET_INFO",
+        18 => "OPLOCK_BREAK",
+        _ => "OTHER",
+    };
+
+    let mut details = format!(
+        "Type:      SMB2/3 {}\nCommand:   {} ({})\nStatus:    0x{:08X}\n",
+        if is_reply { "Response" } else { "Request" },
+        name,
+        command,
+        status
+    );
+    let mut summary = name.to_string();
+
+    // The negotiate response names the dialect both sides settled on
+    if command == 0
+        && is_reply
+        && let Some(d) = smb.get(68..70)
+    {
+        let dialect = match u16::from_le_bytes([d[0], d[1]]) {
+            0x0202 => "2.0.2",
+            0x0210 => "2.1",
+            0x0300 => "3.0",
+            0x0302 => "3.0.2",
+            0x0311 => "3.1.1",
+            _ => "unknown",
+        };
+        details.push_str(&format!("Dialect:   SMB {}\n", dialect));
+        summary = format!("NEGOTIATE SMB {}", dialect);
+    }
+
+    SmbSegment {
+        decoded: DecodedInfo {
+            label: "SMB2".into(),
+            details,
+        },
+        command: Some(summary),
+    }
+}
+
+fn session_message(kind: u8) -> &'static str {
+    match kind {
+        0x00 => "Session Message",
+        0x81 => "Session Request",
+        0x82 => "Positive Session Response",
+        0x83 => "Negative Session Response",
+        0x84 => "Retarget Session Response",
+        0x85 => "Session Keep Alive",
+        _ => "Unknown",
+    }
+}
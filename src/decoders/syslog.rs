@@ -0,0 +1,148 @@
+use super::{DecodedInfo, ProtocolDecoder, uses_port};
+use etherparse::TransportSlice;
+
+/// Syslog on UDP/TCP 514, RFC 3164 (BSD) or RFC 5424 framing.
+pub struct SyslogDecoder;
+
+/// Characters of the message kept in the feed summary; the details show more.
+const SUMMARY_TEXT: usize = 32;
+const DETAIL_TEXT: usize = 200;
+
+const FACILITIES: [&str; 24] = [
+    "kern", "user", "mail", "daemon", "auth", "syslog", "lpr", "news", "uucp", "cron", "authpriv",
+    "ftp", "ntp", "audit", "alert", "clock", "local0", "local1", "local2", "local3", "local4",
+    "local5", "local6", "local7",
+];
+const SEVERITIES: [&str; 8] = [
+    "emerg", "alert", "crit", "err", "warning", "notice", "info", "debug",
+];
+
+/// One syslog message: priority split into facility and severity, and the content.
+struct SyslogMessage {
+    facility: &'static str,
+    pri: usize,
+    format: &'static str,
+    content: String,
+}
+
+impl SyslogMessage {
+    fn severity(&self) -> &'static str {
+        SEVERITIES[self.pri % 8]
+    }
+}
+
+impl ProtocolDecoder for SyslogDecoder {
+    fn decode(&self, transport: &TransportSlice, payload: &[u8]) -> Option<DecodedInfo> {
+        let message = parse(transport, payload)?;
+        // Plain label: it is part of the connection key, so the message text goes in
+        // the feed summary instead (see `summary`)
+        Some(DecodedInfo {
+            label: "SYSLOG".into(),
+            details: format!(
+                "Type:      Syslog ({})\nFacility:  {} ({})\nSeverity:  {} ({})\nMessage:   {}\n",
+                message.format,
+                message.facility,
+                message.pri / 8,
+                message.severity(),
+                message.pri % 8,
+                truncate(&message.content, DETAIL_TEXT)
+            ),
+        })
+    }
+}
+
+/// `local0.info "sshd: Accepted publickey..."` for the feed and summary line.
+pub fn summary(transport: &TransportSlice, payload: &[u8]) -> Option<String> {
+    let message = parse(transport, payload)?;
+    Some(format!(
+        "{}.{} \"{}\"",
+        message.facility,
+        message.severity(),
+        truncate(&message.content, SUMMARY_TEXT)
+    ))
+}
+
+/// The message in a segment to or from port 514, with its `<PRI>` header decoded.
+fn parse(transport: &TransportSlice, payload: &[u8]) -> Option<SyslogMessage> {
+    let message = match transport {
+        TransportSlice::Udp(_) if uses_port(transport, 514) => payload,
+        // RFC 6587 octet counting puts "<length> " in front of each message
+        TransportSlice::Tcp(_) if uses_port(transport, 514) => {
+            match payload.iter().position(|&b| b == b' ') {
+                Some(n) if n > 0 && payload[..n].iter().all(u8::is_ascii_digit) => {
+                    &payload[n + 1..]
+                }
+                _ => payload,
+            }
+        }
+        _ => return None,
+    };
+
+    // <PRI> is 1-3 digits: facility * 8 + severity
+    let text = String::from_utf8_lossy(message);
+    let rest = text.strip_prefix('<')?;
+    let close = rest.find('>').filter(|n| (1..=3).contains(n))?;
+    let pri: usize = rest[..close].parse().ok()?;
+    let facility = FACILITIES.get(pri / 8).copied().unwrap_or("unknown");
+    let body = &rest[close + 1..];
+
+    let (format, content) = match body.strip_prefix("1 ") {
+        Some(header) => ("RFC 5424", rfc5424_content(header)),
+        None => ("RFC 3164", rfc3164_content(body)),
+    };
+    let content = content.trim_end_matches(['\r', '\n', '\0']);
+
+    Some(SyslogMessage {
+        facility,
+        pri,
+        format,
+        content: content.to_string(),
+    })
+}
+
+/// `TIMESTAMP HOSTNAME APP-NAME PROCID MSGID SD MSG`; shown as `app: msg`.
+fn rfc5424_content(header: &str) -> String {
+    let mut fields = header.splitn(6, ' ');
+    let app = fields.nth(2).unwrap_or("-");
+    let after_msgid = fields.nth(2).unwrap_or("");
+    // Structured data is "-" or one or more [id param="value"] blocks
+    let msg = if let Some(rest) = after_msgid.strip_prefix("- ") {
+        rest
+    } else if after_msgid.starts_with('[') {
+        after_msgid
+            .rfind("] ")
+            .map_or("", |end| &after_msgid[end + 2..])
+    } else {
+        after_msgid.trim_start_matches('-')
+    };
+    let msg = msg.trim_start_matches('\u{FEFF}');
+    if app == "-" {
+        msg.to_string()
+    } else {
+        format!("{}: {}", app, msg)
+    }
+}
+
+/// `Mmm dd hh:mm:ss HOSTNAME TAG: msg`; shown from the tag on. Senders that skip the
+/// timestamp and hostname are shown as-is.
+fn rfc3164_content(body: &str) -> String {
+    let has_timestamp = body.len() > 16
+        && body.as_bytes()[3] == b' '
+        && body.as_bytes()[9] == b':'
+        && body.as_bytes()[15] == b' ';
+    if !has_timestamp {
+        return body.to_string();
+    }
+    match body[16..].split_once(' ') {
+        Some((_host, tagged)) => tagged.to_string(),
+        None => body[16..].to_string(),
+    }
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        text.to_string()
+    } else {
+        format!("{}...", text.chars().take(max).collect::<String>())
+    }
+}
@@ -0,0 +1,298 @@
+use super::{DecodedInfo, ProtocolDecoder};
+use etherparse::TransportSlice;
+
+pub struct TlsDecoder;
+
+impl ProtocolDecoder for TlsDecoder {
+    fn decode(&self, transport: &TransportSlice, payload: &[u8]) -> Option<DecodedInfo> {
+        if !matches!(transport, TransportSlice::Tcp(_)) || payload.len() < 5 {
+            return None;
+        }
+        // Record header: content type, legacy version (always 3.x), length
+        let record = match payload[0] {
+            20 => "ChangeCipherSpec",
+            21 => "Alert",
+            22 => "Handshake",
+            23 => "ApplicationData",
+            _ => return None,
+        };
+        if payload[1] != 0x03 || payload[2] > 0x04 {
+            return None;
+        }
+
+        let mut details = format!(
+            "Type:      TLS\nRecord:    {}\nVersion:   0x{:02X}{:02X}\nLength:    {}\n",
+            record,
+            payload[1],
+            payload[2],
+            u16::from_be_bytes([payload[3], payload[4]])
+        );
+        if payload[0] == 22
+            && let Some(&msg) = payload.get(5)
+        {
+            details.push_str(&format!("Handshake: {}\n", handshake_name(msg)));
+
+            if let Some(hello) = hello(payload) {
+                if let Some(name) = &hello.server_name {
+                    details.push_str(&format!("SNI:       {}\n", name));
+                }
+                let version = version_name(hello.version);
+                details.push_str(&format!(
+                    "{}   {}\n",
+                    if msg == 1 { "Offers: " } else { "Chosen: " },
+                    version
+                ));
+                if let Some(cipher) = hello.cipher {
+                    details.push_str(&format!(
+                        "Cipher:    {} (0x{:04X})\n",
+                        cipher_name(cipher),
+                        cipher
+                    ));
+                }
+                if is_deprecated(hello.version) {
+                    details.push_str("Warning:   deprecated protocol version (RFC 8996)\n");
+                }
+            }
+        }
+
+        // The version stays out of the label: it is part of the connection key, and
+        // only the hellos carry it
+        Some(DecodedInfo {
+            label: "TLS".into(),
+            details,
+        })
+    }
+}
+
+/// The hello at the start of `payload`, if it is one. Hellos carry the negotiated
+/// version; everything after them is opaque.
+fn hello(payload: &[u8]) -> Option<Hello> {
+    if payload.len() < 9 || payload[0] != 22 || payload[1] != 0x03 {
+        return None;
+    }
+    match payload[5] {
+        1 => client_hello(&payload[9..]),
+        2 => server_hello(&payload[9..]),
+        _ => None,
+    }
+}
+
+fn handshake_name(msg: u8) -> &'static str {
+    match msg {
+        1 => "ClientHello",
+        2 => "ServerHello",
+        4 => "NewSessionTicket",
+        8 => "EncryptedExtensions",
+        11 => "Certificate",
+        12 => "ServerKeyExchange",
+        13 => "CertificateRequest",
+        14 => "ServerHelloDone",
+        15 => "CertificateVerify",
+        16 => "ClientKeyExchange",
+        20 => "Finished",
+        _ => "Encrypted/Other",
+    }
+}
+
+/// What a ClientHello/ServerHello says about the protocol version.
+struct Hello {
+    /// Highest version offered (ClientHello) or the one chosen (ServerHello)
+    version: u16,
+    /// Only the ServerHello picks a single cipher suite
+    cipher: Option<u16>,
+    /// Host the client asked for (server_name extension), ClientHello only
+    server_name: Option<String>,
+}
+
+/// Feed view of a ClientHello/ServerHello.
+pub struct TlsHello {
+    /// "1.3 → cdn.example.com", "1.0 (deprecated!)"; the server name only when the
+    /// ClientHello sent one
+    pub summary: String,
+    /// SSL 3.0, TLS 1.0 or 1.1
+    pub deprecated: bool,
+}
+
+/// The hello at the start of `payload`, for the feed. `None` for other record or
+/// handshake types, or a hello cut off before its version.
+pub fn parse_hello(payload: &[u8]) -> Option<TlsHello> {
+    let hello = hello(payload)?;
+    let deprecated = is_deprecated(hello.version);
+    let version = version_name(hello.version);
+    let mut summary = version
+        .strip_prefix("TLS")
+        .unwrap_or(&version)
+        .trim()
+        .to_string();
+    if deprecated {
+        summary.push_str(" (deprecated!)");
+    }
+    if let Some(name) = hello.server_name {
+        summary.push_str(&format!(" → {}", name));
+    }
+    Some(TlsHello {
+        summary,
+        deprecated,
+    })
+}
+
+/// Big-endian cursor over handshake bytes; every read fails softly on truncation.
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let (head, tail) = self.data.split_at_checked(len)?;
+        self.data = tail;
+        Some(head)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|b| b[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.take(2).map(|b| u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    /// A vector with a one- or two-byte length prefix.
+    fn vec8(&mut self) -> Option<&'a [u8]> {
+        let len = self.u8()? as usize;
+        self.take(len)
+    }
+
+    fn vec16(&mut self) -> Option<&'a [u8]> {
+        let len = self.u16()? as usize;
+        self.take(len)
+    }
+}
+
+fn client_hello(body: &[u8]) -> Option<Hello> {
+    let mut r = Reader { data: body };
+    let legacy_version = r.u16()?;
+    r.take(32)?; // random
+    r.vec8()?; // session id
+    r.vec16()?; // cipher suites
+    r.vec8()?; // compression methods
+
+    // A hello larger than one segment is cut short here; scan whatever extensions
+    // made it into this one
+    let extensions = match r.u16() {
+        Some(len) => r.data.get(..len as usize).unwrap_or(r.data),
+        None => &[],
+    };
+    let server_name = find_extension(extensions, SERVER_NAME).and_then(|ext| {
+        let mut list = Reader {
+            data: Reader { data: ext }.vec16()?,
+        };
+        // Entry type 0 is host_name, the only one defined
+        while let Some(kind) = list.u8() {
+            let name = list.vec16()?;
+            if kind == 0 {
+                return Some(String::from_utf8_lossy(name).into_owned());
+            }
+        }
+        None
+    });
+
+    // TLS 1.3 clients keep 1.2 in the legacy field and list the real ones in
+    // supported_versions
+    let offered = find_extension(extensions, SUPPORTED_VERSIONS).and_then(|ext| {
+        Reader { data: ext }
+            .vec8()?
+            .chunks_exact(2)
+            .map(|v| u16::from_be_bytes([v[0], v[1]]))
+            .filter(|v| !is_grease(*v))
+            .max()
+    });
+    Some(Hello {
+        version: offered.unwrap_or(legacy_version),
+        cipher: None,
+        server_name,
+    })
+}
+
+fn server_hello(body: &[u8]) -> Option<Hello> {
+    let mut r = Reader { data: body };
+    let legacy_version = r.u16()?;
+    r.take(32)?; // random
+    r.vec8()?; // session id
+    let cipher = r.u16()?;
+    r.u8()?; // compression method
+
+    let chosen = find_extension(r.vec16().unwrap_or_default(), SUPPORTED_VERSIONS)
+        .and_then(|ext| Reader { data: ext }.u16());
+    Some(Hello {
+        version: chosen.unwrap_or(legacy_version),
+        cipher: Some(cipher),
+        server_name: None,
+    })
+}
+
+const SERVER_NAME: u16 = 0;
+const SUPPORTED_VERSIONS: u16 = 43;
+
+fn find_extension(extensions: &[u8], wanted: u16) -> Option<&[u8]> {
+    let mut r = Reader { data: extensions };
+    while let Some(ext_type) = r.u16() {
+        let data = r.vec16()?;
+        if ext_type == wanted {
+            return Some(data);
+        }
+    }
+    None
+}
+
+/// RFC 8701 placeholder values (0x0A0A, 0x1A1A, ...) clients sprinkle into lists.
+fn is_grease(value: u16) -> bool {
+    value & 0x0F0F == 0x0A0A && value >> 8 == value & 0xFF
+}
+
+fn version_name(version: u16) -> String {
+    match version {
+        0x0300 => "SSL3.0".into(),
+        0x0301 => "TLS1.0".into(),
+        0x0302 => "TLS1.1".into(),
+        0x0303 => "TLS1.2".into(),
+        0x0304 => "TLS1.3".into(),
+        other => format!("TLS 0x{:04X}", other),
+    }
+}
+
+/// SSL 3.0, TLS 1.0 and TLS 1.1 are deprecated by RFC 8996.
+fn is_deprecated(version: u16) -> bool {
+    (0x0300..=0x0302).contains(&version)
+}
+
+fn cipher_name(cipher: u16) -> &'static str {
+    match cipher {
+        0x1301 => "TLS_AES_128_GCM_SHA256",
+        0x1302 => "TLS_AES_256_GCM_SHA384",
+        0x1303 => "TLS_CHACHA20_POLY1305_SHA256",
+        0xC02B => "TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256",
+        0xC02C => "TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384",
+        0xC02F => "TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256",
+        0xC030 => "TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384",
+        0xCCA8 => "TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256",
+        0xCCA9 => "TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256",
+        0xC009 => "TLS_ECDHE_ECDSA_WITH_AES_128_CBC_SHA",
+        0xC00A => "TLS_ECDHE_ECDSA_WITH_AES_256_CBC_SHA",
+        0xC013 => "TLS_ECDHE_RSA_WITH_AES_128_CBC_SHA",
+        0xC014 => "TLS_ECDHE_RSA_WITH_AES_256_CBC_SHA",
+        0xC027 => "TLS_ECDHE_RSA_WITH_AES_128_CBC_SHA256",
+        0xC028 => "TLS_ECDHE_RSA_WITH_AES_256_CBC_SHA384",
+        0x009C => "TLS_RSA_WITH_AES_128_GCM_SHA256",
+        0x009D => "TLS_RSA_WITH_AES_256_GCM_SHA384",
+        0x009E => "TLS_DHE_RSA_WITH_AES_128_GCM_SHA256",
+        0x009F => "TLS_DHE_RSA_WITH_AES_256_GCM_SHA384",
+        0x003C => "TLS_RSA_WITH_AES_128_CBC_SHA256",
+        0x003D => "TLS_RSA_WITH_AES_256_CBC_SHA256",
+        0x002F => "TLS_RSA_WITH_AES_128_CBC_SHA",
+        0x0035 => "TLS_RSA_WITH_AES_256_CBC_SHA",
+        0x000A => "TLS_RSA_WITH_3DES_EDE_CBC_SHA",
+        0x0005 => "TLS_RSA_WITH_RC4_128_SHA",
+        0x0004 => "TLS_RSA_WITH_RC4_128_MD5",
+        _ => "unknown",
+    }
+}
@@ -0,0 +1,43 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// How far apart two identical frames can arrive and still count as a mirror copy.
+const DUP_WINDOW: Duration = Duration::from_millis(100);
+/// Fingerprints remembered at once; old entries fall off the front.
+const DUP_CAPACITY: usize = 256;
+
+/// Small LRU of recent frame fingerprints, used to suppress the second copy of a
+/// packet on spanned/mirrored ports.
+pub struct Deduplicator {
+    recent: VecDeque<(u64, Instant)>,
+}
+
+impl Deduplicator {
+    pub fn new() -> Self {
+        Self {
+            recent: VecDeque::with_capacity(DUP_CAPACITY),
+        }
+    }
+
+    /// Returns true when an identical frame was seen within the window; otherwise
+    /// remembers this one and returns false.
+    pub fn is_duplicate(&mut self, fingerprint: u64, at: Instant) -> bool {
+        while let Some(&(_, seen)) = self.recent.front() {
+            if at.saturating_duration_since(seen) > DUP_WINDOW {
+                self.recent.pop_front();
+            } else {
+                break;
+            }
+        }
+
+        if self.recent.iter().any(|&(fp, _)| fp == fingerprint) {
+            return true;
+        }
+
+        if self.recent.len() == DUP_CAPACITY {
+            self.recent.pop_front();
+        }
+        self.recent.push_back((fingerprint, at));
+        false
+    }
+}
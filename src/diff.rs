@@ -0,0 +1,113 @@
+use crate::anonymize::Anonymizer;
+use crate::capture::{ParseState, parse_packet_full};
+use crate::keymap::{Action, KeyMap};
+use crate::screen;
+use crate::{ConnectionKey, KeyGranularity, connection_key, ui};
+use crossterm::event::{self, Event, KeyCode};
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Connection sets of two captures, split by where each key shows up.
+pub struct ConnectionDiff {
+    pub only_a: Vec<(ConnectionKey, u64)>,
+    /// Key with its byte totals in A and in B
+    pub common: Vec<(ConnectionKey, u64, u64)>,
+    pub only_b: Vec<(ConnectionKey, u64)>,
+}
+
+/// `--diff a.pcap b.pcap`: aggregates both files, then shows the three-column view
+/// until the user quits. With `anonymize`, one pseudonym table covers both files so a
+/// host keeps its name across the columns.
+pub fn run(
+    a: &Path,
+    b: &Path,
+    keymap: &KeyMap,
+    anonymize: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut anonymizer = anonymize.then(Anonymizer::new);
+    let diff = compare(
+        &aggregate(a, anonymizer.as_mut())?,
+        &aggregate(b, anonymizer.as_mut())?,
+    );
+    let a_name = a.display().to_string();
+    let b_name = b.display().to_string();
+
+    let mut terminal = screen::enter(false)?;
+
+    let mut offset = 0usize;
+    let longest = diff
+        .only_a
+        .len()
+        .max(diff.common.len())
+        .max(diff.only_b.len());
+    loop {
+        terminal.draw(|f| ui::draw_diff(f, &a_name, &b_name, &diff, offset, keymap))?;
+
+        if let Event::Key(key) = event::read()? {
+            match keymap.action_for(&key) {
+                Some(Action::Quit) => break,
+                Some(Action::Down) => offset = (offset + 1).min(longest.saturating_sub(1)),
+                Some(Action::Up) => offset = offset.saturating_sub(1),
+                _ if key.code == KeyCode::Esc => break,
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Reads a capture file through the normal parser and sums bytes per connection.
+fn aggregate(
+    path: &Path,
+    mut anonymizer: Option<&mut Anonymizer>,
+) -> Result<HashMap<ConnectionKey, u64>, String> {
+    let mut cap = pcap::Capture::from_file(path)
+        .map_err(|e| format!("Cannot open {}: {}", path.display(), e))?;
+    let linktype = cap.get_datalink();
+    let mut parse_state = ParseState::new();
+
+    // Nothing in a file is known to be local, so rows are oriented client -> server
+    let local = HashSet::new();
+    let mut connections = HashMap::new();
+    while let Ok(packet) = cap.next_packet() {
+        let mut parsed = parse_packet_full(packet.data, linktype, &mut parse_state);
+        if let Some(anonymizer) = anonymizer.as_deref_mut() {
+            anonymizer.apply(&mut parsed);
+        }
+        *connections
+            .entry(connection_key(
+                &parsed,
+                false,
+                KeyGranularity::Proto,
+                &local,
+            ))
+            .or_insert(0) += parsed.length as u64;
+    }
+    Ok(connections)
+}
+
+fn compare(a: &HashMap<ConnectionKey, u64>, b: &HashMap<ConnectionKey, u64>) -> ConnectionDiff {
+    let mut diff = ConnectionDiff {
+        only_a: Vec::new(),
+        common: Vec::new(),
+        only_b: Vec::new(),
+    };
+    for (key, bytes_a) in a {
+        match b.get(key) {
+            Some(bytes_b) => diff.common.push((key.clone(), *bytes_a, *bytes_b)),
+            None => diff.only_a.push((key.clone(), *bytes_a)),
+        }
+    }
+    for (key, bytes_b) in b {
+        if !a.contains_key(key) {
+            diff.only_b.push((key.clone(), *bytes_b));
+        }
+    }
+
+    // Largest first, like the Connections tab
+    diff.only_a.sort_by_key(|entry| Reverse(entry.1));
+    diff.common.sort_by_key(|entry| Reverse(entry.1 + entry.2));
+    diff.only_b.sort_by_key(|entry| Reverse(entry.1));
+    diff
+}
@@ -0,0 +1,134 @@
+use crate::capture::PacketData;
+use crate::{ConnectionKey, ConnectionStats};
+use chrono::{DateTime, Local, SecondsFormat, TimeDelta};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+
+const THROUGHPUT_HEADER: &str = "timestamp,bytes";
+
+/// Writes the per-second throughput buckets to a CSV. The last bucket is the second
+/// that ended at `last_bucket`; earlier ones are stamped one second apart going back.
+pub fn write_throughput_csv(
+    path: &Path,
+    history: &[u64],
+    last_bucket: DateTime<Local>,
+) -> io::Result<()> {
+    let mut file = File::create(path)?;
+    writeln!(file, "{}", THROUGHPUT_HEADER)?;
+    for (i, bytes) in history.iter().enumerate() {
+        let age = (history.len() - 1 - i) as i64;
+        let at = last_bucket - TimeDelta::seconds(age);
+        writeln!(file, "{},{}", at.format("%Y-%m-%dT%H:%M:%S%:z"), bytes)?;
+    }
+    Ok(())
+}
+
+/// `--throughput-log`: appends every completed second, so recording isn't limited
+/// to the chart's rolling window.
+pub struct ThroughputLog {
+    file: File,
+}
+
+impl ThroughputLog {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let is_new = !path.exists();
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        if is_new {
+            writeln!(file, "{}", THROUGHPUT_HEADER)?;
+        }
+        Ok(Self { file })
+    }
+
+    pub fn append(&mut self, at: DateTime<Local>, bytes: u64) -> io::Result<()> {
+        writeln!(self.file, "{},{}", at.format("%Y-%m-%dT%H:%M:%S%:z"), bytes)
+    }
+}
+
+/// Connections rows in display order, for a report.
+pub fn write_connections_csv(
+    path: &Path,
+    rows: &[(&ConnectionKey, &ConnectionStats)],
+) -> io::Result<()> {
+    let mut file = File::create(path)?;
+    writeln!(file, "source,dest,protocol,app,bytes")?;
+    for ((source, dest, proto, app), stats) in rows {
+        writeln!(
+            file,
+            "{},{},{},{},{}",
+            csv_field(source),
+            csv_field(dest),
+            csv_field(proto),
+            csv_field(app),
+            stats.bytes
+        )?;
+    }
+    Ok(())
+}
+
+/// Feed packets as JSON Lines, one object per packet, for jq or pandas.
+pub fn write_feed_jsonl(path: &Path, packets: &[&PacketData]) -> io::Result<()> {
+    let mut file = File::create(path)?;
+    for packet in packets {
+        let timestamp = DateTime::from_timestamp_micros(packet.captured_micros)
+            .map(|at| {
+                at.with_timezone(&Local)
+                    .to_rfc3339_opts(SecondsFormat::Micros, false)
+            })
+            .unwrap_or_default();
+        writeln!(
+            file,
+            "{{\"timestamp\":{},\"source\":{},\"dest\":{},\"proto_label\":{},\"app_name\":{},\"length\":{}}}",
+            json_string(&timestamp),
+            json_string(&packet.source),
+            json_string(&packet.dest),
+            json_string(&packet.proto_label),
+            json_string(&packet.app_name),
+            packet.length
+        )?;
+    }
+    Ok(())
+}
+
+/// A JSON string literal, escaping quotes, backslashes and control characters.
+fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Quotes a value containing a comma, quote or newline (app names can).
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// One packet's decode and hex dump as plain text, for attaching to a ticket.
+pub fn write_packet_txt(path: &Path, packet: &PacketData) -> io::Result<()> {
+    let mut file = File::create(path)?;
+    writeln!(file, "Time:   {}", packet.time_label)?;
+    writeln!(file, "App:    {}", packet.app_name)?;
+    writeln!(file, "Length: {} bytes", packet.length)?;
+    writeln!(file, "Summary: {}", packet.summary)?;
+    writeln!(file)?;
+    writeln!(file, "{}", packet.full_details.trim_end())?;
+    writeln!(file)?;
+    writeln!(file, "--- HEX DUMP ---")?;
+    writeln!(file, "{}", packet.hex_dump.trim_end())?;
+    Ok(())
+}
@@ -0,0 +1,286 @@
+//! Country lookup in a MaxMind DB (`.mmdb`) file such as GeoLite2-Country, for
+//! `--geoip`.
+//!
+//! Only what a country lookup needs is read: the metadata, the binary search tree and
+//! the maps, strings and integers of the data section. Without `--geoip` every lookup
+//! returns `None`.
+
+use crate::capture::PacketData;
+use std::fs;
+use std::net::IpAddr;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Precedes the metadata map at the end of the file.
+const METADATA_MARKER: &[u8] = b"\xAB\xCD\xEFMaxMind.com";
+/// Zero bytes between the search tree and the data section.
+const DATA_SEPARATOR: usize = 16;
+/// Nesting allowed in a record before the file is treated as corrupt.
+const MAX_DEPTH: u8 = 32;
+
+static DATABASE: OnceLock<Option<Database>> = OnceLock::new();
+
+/// Loads `path`, if given. Without a call every lookup returns `None`.
+pub fn init(path: Option<&Path>) -> Result<(), String> {
+    let database = path.map(Database::open).transpose()?;
+    let _ = DATABASE.set(database);
+    Ok(())
+}
+
+/// Two-letter country code of `ip`, falling back to the country the network is
+/// registered in (anycast and satellite ranges often only have that).
+pub fn country(ip: IpAddr) -> Option<String> {
+    DATABASE.get()?.as_ref()?.country(ip)
+}
+
+/// Country of the far end of `packet`, seen from the side [`crate::is_outbound`]
+/// picked as the source. The near end is tried too, for traffic between two hosts
+/// that are both remote.
+pub fn remote_country(packet: &PacketData, outbound: bool) -> Option<String> {
+    DATABASE.get()?.as_ref()?;
+    let (near, far) = if outbound {
+        (&packet.source, &packet.dest)
+    } else {
+        (&packet.dest, &packet.source)
+    };
+    [far, near]
+        .into_iter()
+        .find_map(|addr| country(addr.parse().ok()?))
+}
+
+struct Database {
+    bytes: Vec<u8>,
+    node_count: usize,
+    /// Bits per search tree record: 24, 28 or 32
+    record_size: usize,
+    ip_version: u64,
+    /// Start of the data section, past the search tree and its separator
+    data_start: usize,
+    /// Node reached after 96 zero bits, where IPv4 addresses begin in an IPv6 tree
+    ipv4_start: usize,
+}
+
+impl Database {
+    fn open(path: &Path) -> Result<Self, String> {
+        let bytes = fs::read(path)
+            .map_err(|e| format!("Cannot read GeoIP database {}: {}", path.display(), e))?;
+        let invalid = || format!("{} is not a MaxMind DB file", path.display());
+
+        let marker = bytes
+            .windows(METADATA_MARKER.len())
+            .rposition(|window| window == METADATA_MARKER)
+            .ok_or_else(invalid)?;
+        let metadata_section = &bytes[marker + METADATA_MARKER.len()..];
+        let (metadata, _) = decode(metadata_section, 0, 0).ok_or_else(invalid)?;
+        let field = |name| metadata.get(name).and_then(Value::as_uint);
+        let node_count = field("node_count").ok_or_else(invalid)? as usize;
+        let record_size = field("record_size").ok_or_else(invalid)? as usize;
+        let ip_version = field("ip_version").ok_or_else(invalid)?;
+        if !matches!(record_size, 24 | 28 | 32) {
+            return Err(format!(
+                "{}: unsupported record size {}",
+                path.display(),
+                record_size
+            ));
+        }
+        // Two records per node
+        let data_start = node_count * record_size / 4 + DATA_SEPARATOR;
+        if data_start > marker {
+            return Err(invalid());
+        }
+
+        let mut database = Self {
+            bytes,
+            node_count,
+            record_size,
+            ip_version,
+            data_start,
+            ipv4_start: 0,
+        };
+        if ip_version == 6 {
+            let mut node = 0;
+            for _ in 0..96 {
+                if node >= node_count {
+                    break;
+                }
+                node = database.record(node, 0);
+            }
+            database.ipv4_start = node;
+        }
+        log::info!(
+            "loaded GeoIP database {} ({} nodes, IPv{})",
+            path.display(),
+            node_count,
+            ip_version
+        );
+        Ok(database)
+    }
+
+    fn country(&self, ip: IpAddr) -> Option<String> {
+        let (octets, mut node) = match ip {
+            IpAddr::V4(v4) => (v4.octets().to_vec(), self.ipv4_start),
+            IpAddr::V6(_) if self.ip_version == 4 => return None,
+            IpAddr::V6(v6) => (v6.octets().to_vec(), 0),
+        };
+        for i in 0..octets.len() * 8 {
+            if node >= self.node_count {
+                break;
+            }
+            let bit = (octets[i / 8] >> (7 - i % 8)) & 1;
+            node = self.record(node, bit);
+        }
+        // Equal to node_count means "no data"; anything larger points into the data
+        let offset = node.checked_sub(self.node_count + DATA_SEPARATOR)?;
+        let (record, _) = decode(&self.bytes[self.data_start..], offset, 0)?;
+        ["country", "registered_country"]
+            .iter()
+            .find_map(|key| record.get(key)?.get("iso_code")?.as_str())
+            .map(str::to_string)
+    }
+
+    /// Left (`bit` 0) or right record of `node`.
+    fn record(&self, node: usize, bit: u8) -> usize {
+        let bit = bit as usize;
+        match self.record_size {
+            24 => {
+                let at = node * 6 + bit * 3;
+                big_endian(&self.bytes[at..at + 3])
+            }
+            28 => {
+                // Each record's top four bits share the middle byte
+                let at = node * 7;
+                let middle = self.bytes[at + 3] as usize;
+                if bit == 0 {
+                    ((middle & 0xF0) << 20) | big_endian(&self.bytes[at..at + 3])
+                } else {
+                    ((middle & 0x0F) << 24) | big_endian(&self.bytes[at + 4..at + 7])
+                }
+            }
+            _ => {
+                let at = node * 8 + bit * 4;
+                big_endian(&self.bytes[at..at + 4])
+            }
+        }
+    }
+}
+
+/// The data section types a country lookup reads; everything else is skipped.
+enum Value {
+    Map(Vec<(String, Value)>),
+    Str(String),
+    Uint(u64),
+    Other,
+}
+
+impl Value {
+    fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Map(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn as_uint(&self) -> Option<u64> {
+        match self {
+            Value::Uint(n) => Some(*n),
+            _ => None,
+        }
+    }
+}
+
+/// The value at `at` in `section` and the offset just past it. Pointers are followed,
+/// relative to the start of `section`.
+fn decode(section: &[u8], at: usize, depth: u8) -> Option<(Value, usize)> {
+    if depth > MAX_DEPTH {
+        return None;
+    }
+    let control = *section.get(at)?;
+    let mut at = at + 1;
+    let mut kind = u16::from(control >> 5);
+
+    if kind == 1 {
+        let extra = ((control >> 3) & 0x3) as usize + 1;
+        let bytes = section.get(at..at + extra)?;
+        let low = (control & 0x7) as usize;
+        let target = match extra {
+            1 => (low << 8) | big_endian(bytes),
+            2 => ((low << 16) | big_endian(bytes)) + 2048,
+            3 => ((low << 24) | big_endian(bytes)) + 526_336,
+            _ => big_endian(bytes),
+        };
+        let (value, _) = decode(section, target, depth + 1)?;
+        return Some((value, at + extra));
+    }
+    if kind == 0 {
+        // Extended types run 8 (int32) to 15 (float)
+        kind = 7 + u16::from(*section.get(at)?);
+        if !(8..=15).contains(&kind) {
+            return None;
+        }
+        at += 1;
+    }
+
+    let mut size = (control & 0x1F) as usize;
+    if size >= 29 {
+        let extra = size - 28;
+        let bytes = section.get(at..at + extra)?;
+        at += extra;
+        size = match extra {
+            1 => 29 + big_endian(bytes),
+            2 => 285 + big_endian(bytes),
+            _ => 65_821 + big_endian(bytes),
+        };
+    }
+
+    match kind {
+        // UTF-8 string
+        2 => {
+            let bytes = section.get(at..at + size)?;
+            let text = String::from_utf8_lossy(bytes).into_owned();
+            Some((Value::Str(text), at + size))
+        }
+        // uint16, uint32, uint64
+        5 | 6 | 9 if size <= 8 => {
+            let bytes = section.get(at..at + size)?;
+            let n = bytes.iter().fold(0u64, |n, b| (n << 8) | *b as u64);
+            Some((Value::Uint(n), at + size))
+        }
+        7 => {
+            let mut entries = Vec::with_capacity(size.min(64));
+            for _ in 0..size {
+                let (key, next) = decode(section, at, depth + 1)?;
+                let (value, next) = decode(section, next, depth + 1)?;
+                entries.push((key.as_str()?.to_string(), value));
+                at = next;
+            }
+            Some((Value::Map(entries), at))
+        }
+        // Array: walked so the offset after it is right, contents dropped
+        11 => {
+            for _ in 0..size {
+                at = decode(section, at, depth + 1)?.1;
+            }
+            Some((Value::Other, at))
+        }
+        // Boolean: the value is the size, no payload
+        14 => Some((Value::Other, at)),
+        // Double, bytes, int32, wide unsigned integers, float: skipped
+        3 | 4 | 5 | 6 | 8 | 9 | 10 | 15 => {
+            section.get(at..at + size)?;
+            Some((Value::Other, at + size))
+        }
+        // Data cache container and end marker never appear in a record
+        _ => None,
+    }
+}
+
+fn big_endian(bytes: &[u8]) -> usize {
+    bytes.iter().fold(0, |n, b| (n << 8) | *b as usize)
+}
@@ -0,0 +1,331 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Everything the Normal-mode input handler can do, independent of which key triggers it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    FeedTab,
+    ConnectionsTab,
+    NextTab,
+    Search,
+    /// Turn the search text into a capture filter that discards non-matching packets
+    PromoteFilter,
+    FilterApp,
+    ToggleDetail,
+    TogglePause,
+    ClearFeed,
+    ClearConnections,
+    ToggleSave,
+    Down,
+    Up,
+    /// Move the selection by a screenful of rows
+    PageDown,
+    PageUp,
+    /// Jump to the first / last row of the list
+    Home,
+    End,
+    SpikeLeft,
+    SpikeRight,
+    CycleChartScale,
+    /// Throughput readouts in bytes/s or bits/s
+    ToggleRateUnit,
+    ToggleLength,
+    TogglePorts,
+    /// Bandwidth-by-process ranking overlay
+    ToggleApps,
+    /// Share of traffic by protocol label overlay
+    ToggleProtocols,
+    /// Overlay of the slowest recent DNS lookups
+    ToggleSlowDns,
+    /// Show reverse-DNS names instead of remote addresses in the feed
+    ToggleHostnames,
+    /// Let SSDP and other discovery chatter through to the feed
+    ToggleNoise,
+    ExportThroughput,
+    /// Write the Connections rows matching the current filters to a CSV
+    ExportConnections,
+    /// Write the feed rows matching the current filters as JSON Lines
+    ExportFeed,
+    /// Write the selected feed packet's decode and hex dump to a text file
+    ExportPacket,
+    /// Keep the inspector on the selected packet while the selection moves on
+    PinInspector,
+    /// Inspector shows summary / details / hex / raw bytes
+    CycleDetailView,
+    /// Remember the current connection keys so later ones are marked NEW
+    SnapshotConnections,
+    /// Spike analysis window as "Ns ago" or wall-clock time
+    ToggleSpikeTime,
+    /// Group connections by host / +proto / +port / 5-tuple
+    CycleGranularity,
+    /// Order connections by bytes / app / protocol / source
+    CycleSort,
+    /// Flip the Connections order between ascending and descending
+    ReverseSort,
+    /// Collapse connections differing only in an ephemeral client port
+    ToggleEphemeralFold,
+    /// Flip the feed's quick-filter chip at this index in `ui::CHIPS`
+    ToggleChip(usize),
+}
+
+impl Action {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name.to_lowercase().as_str() {
+            "quit" => Action::Quit,
+            "feedtab" => Action::FeedTab,
+            "connectionstab" => Action::ConnectionsTab,
+            "nexttab" => Action::NextTab,
+            "search" => Action::Search,
+            "promotefilter" => Action::PromoteFilter,
+            "filterapp" => Action::FilterApp,
+            "toggledetail" => Action::ToggleDetail,
+            "togglepause" => Action::TogglePause,
+            // "clear" predates the split into feed/connections
+            "clear" | "clearfeed" => Action::ClearFeed,
+            "clearconnections" => Action::ClearConnections,
+            "togglesave" => Action::ToggleSave,
+            "down" => Action::Down,
+            "up" => Action::Up,
+            "pagedown" => Action::PageDown,
+            "pageup" => Action::PageUp,
+            "home" => Action::Home,
+            "end" => Action::End,
+            "spikeleft" => Action::SpikeLeft,
+            "spikeright" => Action::SpikeRight,
+            "cyclechartscale" => Action::CycleChartScale,
+            "togglerateunit" => Action::ToggleRateUnit,
+            "togglelength" => Action::ToggleLength,
+            "toggleports" => Action::TogglePorts,
+            "toggleapps" => Action::ToggleApps,
+            "toggleprotocols" => Action::ToggleProtocols,
+            "toggleslowdns" => Action::ToggleSlowDns,
+            "togglehostnames" => Action::ToggleHostnames,
+            "togglenoise" => Action::ToggleNoise,
+            "exportthroughput" => Action::ExportThroughput,
+            "exportconnections" => Action::ExportConnections,
+            "exportpacket" => Action::ExportPacket,
+            "exportfeed" => Action::ExportFeed,
+            "pininspector" => Action::PinInspector,
+            "cycledetailview" => Action::CycleDetailView,
+            "snapshotconnections" => Action::SnapshotConnections,
+            "togglespiketime" => Action::ToggleSpikeTime,
+            "cyclegranularity" => Action::CycleGranularity,
+            "toggleephemeralfold" => Action::ToggleEphemeralFold,
+            "cyclesort" => Action::CycleSort,
+            "reversesort" => Action::ReverseSort,
+            chip => {
+                let index: usize = chip.strip_prefix("togglechip")?.parse().ok()?;
+                Action::ToggleChip(
+                    index
+                        .checked_sub(1)
+                        .filter(|i| *i < crate::ui::CHIPS.len())?,
+                )
+            }
+        })
+    }
+}
+
+/// Today's hardcoded bindings; a config file only overrides the actions it mentions.
+const DEFAULT_BINDINGS: &[(Action, &[&str])] = &[
+    (Action::Quit, &["q"]),
+    (Action::FeedTab, &["1"]),
+    (Action::ConnectionsTab, &["2"]),
+    (Action::NextTab, &["tab"]),
+    (Action::Search, &["/"]),
+    (Action::PromoteFilter, &["f"]),
+    (Action::FilterApp, &["a"]),
+    (Action::ToggleDetail, &["i"]),
+    (Action::TogglePause, &["space"]),
+    (Action::ClearFeed, &["c"]),
+    (Action::ClearConnections, &["C"]),
+    (Action::ToggleSave, &["w"]),
+    (Action::Down, &["j", "down"]),
+    (Action::Up, &["k", "up"]),
+    (Action::PageDown, &["pagedown"]),
+    (Action::PageUp, &["pageup"]),
+    (Action::Home, &["home"]),
+    (Action::End, &["end"]),
+    (Action::SpikeLeft, &["left"]),
+    (Action::SpikeRight, &["right"]),
+    (Action::CycleChartScale, &["s"]),
+    (Action::ToggleRateUnit, &["b"]),
+    (Action::ToggleLength, &["l"]),
+    (Action::TogglePorts, &["p"]),
+    (Action::ToggleApps, &["P"]),
+    (Action::ToggleProtocols, &["B"]),
+    (Action::ToggleSlowDns, &["D"]),
+    (Action::ToggleHostnames, &["r"]),
+    (Action::ToggleNoise, &["M"]),
+    (Action::ExportThroughput, &["x"]),
+    (Action::ExportConnections, &["e"]),
+    (Action::ExportPacket, &["o"]),
+    (Action::ExportFeed, &["O"]),
+    (Action::PinInspector, &["v"]),
+    (Action::CycleDetailView, &["d"]),
+    (Action::SnapshotConnections, &["m"]),
+    (Action::ToggleSpikeTime, &["t"]),
+    (Action::CycleGranularity, &["g"]),
+    (Action::ToggleEphemeralFold, &["F"]),
+    (Action::CycleSort, &["S"]),
+    (Action::ReverseSort, &["A"]),
+    // 1 and 2 already pick the tab
+    (Action::ToggleChip(0), &["3"]),
+    (Action::ToggleChip(1), &["4"]),
+    (Action::ToggleChip(2), &["5"]),
+    (Action::ToggleChip(3), &["6"]),
+    (Action::ToggleChip(4), &["7"]),
+    (Action::ToggleChip(5), &["8"]),
+];
+
+type Binding = (KeyCode, KeyModifiers);
+
+pub struct KeyMap {
+    bindings: HashMap<Binding, Action>,
+    labels: HashMap<Action, String>,
+}
+
+impl KeyMap {
+    /// Loads the defaults, then applies overrides from `path` (or the default config
+    /// location, if that file exists).
+    ///
+    /// The file format is one `Action = key[, key...]` per line, e.g. `Quit = ctrl+c, q`.
+    pub fn load(path: Option<&Path>) -> Result<Self, String> {
+        let mut per_action: HashMap<Action, Vec<String>> = DEFAULT_BINDINGS
+            .iter()
+            .map(|(action, keys)| (*action, keys.iter().map(|k| k.to_string()).collect()))
+            .collect();
+
+        let config = match path {
+            Some(p) => Some(
+                fs::read_to_string(p)
+                    .map_err(|e| format!("Cannot read keymap {}: {}", p.display(), e))?,
+            ),
+            None => default_config_path().and_then(|p| fs::read_to_string(p).ok()),
+        };
+
+        if let Some(config) = config {
+            for (line_no, line) in config.lines().enumerate() {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+                let (name, keys) = line.split_once('=').ok_or_else(|| {
+                    format!("Keymap line {}: expected 'Action = key'", line_no + 1)
+                })?;
+                let action = Action::from_name(name.trim()).ok_or_else(|| {
+                    format!(
+                        "Keymap line {}: unknown action '{}'",
+                        line_no + 1,
+                        name.trim()
+                    )
+                })?;
+                let keys: Vec<String> = keys
+                    .split(',')
+                    .map(|k| k.trim().to_string())
+                    .filter(|k| !k.is_empty())
+                    .collect();
+                per_action.insert(action, keys);
+            }
+        }
+
+        let mut keymap = KeyMap {
+            bindings: HashMap::new(),
+            labels: HashMap::new(),
+        };
+        for (action, keys) in per_action {
+            for key in &keys {
+                let binding =
+                    parse_binding(key).ok_or_else(|| format!("Keymap: unknown key '{}'", key))?;
+                keymap.bindings.insert(binding, action);
+            }
+            if let Some(first) = keys.first() {
+                keymap.labels.insert(action, display_label(first));
+            }
+        }
+        Ok(keymap)
+    }
+
+    pub fn action_for(&self, key: &KeyEvent) -> Option<Action> {
+        let mut modifiers = key.modifiers;
+        // Terminals disagree on whether uppercase letters also report SHIFT
+        if let KeyCode::Char(_) = key.code {
+            modifiers -= KeyModifiers::SHIFT;
+        }
+        self.bindings.get(&(key.code, modifiers)).copied()
+    }
+
+    /// Short label of the first key bound to `action`, for the footer hints.
+    pub fn label(&self, action: Action) -> &str {
+        self.labels.get(&action).map(String::as_str).unwrap_or("?")
+    }
+}
+
+fn default_config_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")))?;
+    Some(base.join("net-sniff").join("keys.conf"))
+}
+
+fn parse_binding(spec: &str) -> Option<Binding> {
+    let mut parts: Vec<&str> = spec.split('+').collect();
+    let rest = parts.pop()?;
+    let mut modifiers = KeyModifiers::NONE;
+    for part in parts {
+        modifiers |= match part.to_lowercase().as_str() {
+            "ctrl" => KeyModifiers::CONTROL,
+            "alt" => KeyModifiers::ALT,
+            "shift" => KeyModifiers::SHIFT,
+            _ => return None,
+        };
+    }
+
+    let code = match rest.to_lowercase().as_str() {
+        "space" => KeyCode::Char(' '),
+        "enter" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backspace" => KeyCode::Backspace,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        f if f.len() > 1 && f.starts_with('f') => KeyCode::F(f[1..].parse().ok()?),
+        _ => {
+            let mut chars = rest.chars();
+            let c = chars.next()?;
+            if chars.next().is_some() {
+                return None;
+            }
+            KeyCode::Char(c)
+        }
+    };
+    // Bindings are stored the way terminals report them: `shift+c` arrives as 'C'
+    if let KeyCode::Char(c) = code
+        && modifiers.contains(KeyModifiers::SHIFT)
+    {
+        return Some((
+            KeyCode::Char(c.to_ascii_uppercase()),
+            modifiers - KeyModifiers::SHIFT,
+        ));
+    }
+    Some((code, modifiers))
+}
+
+fn display_label(spec: &str) -> String {
+    match spec.to_lowercase().as_str() {
+        "space" => "Space".into(),
+        "left" => "←".into(),
+        "right" => "→".into(),
+        "up" => "↑".into(),
+        "down" => "↓".into(),
+        _ => spec.to_string(),
+    }
+}
@@ -0,0 +1,75 @@
+use crate::capture::PacketData;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Queries still unanswered after this long are given up on.
+const QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+/// Lookups at least this slow go on the slow-DNS list and are highlighted in the feed.
+pub const SLOW_LOOKUP: Duration = Duration::from_millis(200);
+/// Most recent slow lookups kept for the overlay.
+const SLOW_KEPT: usize = 50;
+
+/// A resolved lookup that took longer than [`SLOW_LOOKUP`].
+pub struct SlowLookup {
+    pub name: String,
+    pub server: String,
+    pub latency: Duration,
+    pub time_label: String,
+}
+
+/// Pairs DNS queries with their responses to time each lookup.
+pub struct DnsLatency {
+    /// (client, server, transaction ID) -> when the query was seen and what it asked
+    pending: HashMap<(String, String, u16), (Instant, Option<String>)>,
+    /// Newest first
+    pub slow: VecDeque<SlowLookup>,
+}
+
+impl DnsLatency {
+    pub fn new() -> Self {
+        Self {
+            pending: HashMap::new(),
+            slow: VecDeque::new(),
+        }
+    }
+
+    /// Records a query, or returns the lookup time when `packet` answers a pending one.
+    /// Responses only match a query with the same ID and question name, since IDs alone
+    /// collide between clients behind the same resolver.
+    pub fn observe(&mut self, packet: &PacketData) -> Option<Duration> {
+        let dns = packet.dns.as_ref()?;
+        if !dns.is_response {
+            let now = packet.timestamp;
+            self.pending
+                .retain(|_, (asked, _)| now.duration_since(*asked) < QUERY_TIMEOUT);
+            self.pending.insert(
+                (packet.source.clone(), packet.dest.clone(), dns.id),
+                (now, dns.name.clone()),
+            );
+            return None;
+        }
+
+        let key = (packet.dest.clone(), packet.source.clone(), dns.id);
+        match self.pending.get(&key) {
+            Some((_, name)) if *name == dns.name => {}
+            _ => return None,
+        }
+        let (asked, name) = self.pending.remove(&key)?;
+        let latency = packet.timestamp.saturating_duration_since(asked);
+        if latency >= SLOW_LOOKUP {
+            self.slow.push_front(SlowLookup {
+                name: name.unwrap_or_else(|| "?".into()),
+                server: packet.source.clone(),
+                latency,
+                time_label: packet.time_label.clone(),
+            });
+            self.slow.truncate(SLOW_KEPT);
+        }
+        Some(latency)
+    }
+
+    pub fn clear(&mut self) {
+        self.pending.clear();
+        self.slow.clear();
+    }
+}
@@ -1,26 +1,301 @@
+mod anonymize;
 mod capture;
+mod cli;
+mod decoders;
+mod dedup;
+mod diff;
+mod export;
+mod geoip;
+mod keymap;
+mod latency;
+mod logging;
+mod memory;
+mod noise;
+mod oui;
+mod preflight;
 mod process;
+mod rdns;
+mod reassembly;
+mod recorder;
+mod rtp;
+mod screen;
+mod search;
+mod services;
+mod tcp;
 mod ui;
+mod wifi;
 
-use crate::capture::{PacketData, parse_packet_full};
+use crate::anonymize::Anonymizer;
+use crate::capture::{
+    PacketData, ParseState, SourceInfo, enforce_raw_budget, parse_packet_full, unmap_ipv4,
+};
+use crate::cli::Args;
+use crate::dedup::Deduplicator;
+use crate::export::{
+    ThroughputLog, write_connections_csv, write_feed_jsonl, write_packet_txt, write_throughput_csv,
+};
+use crate::keymap::{Action, KeyMap};
+use crate::latency::DnsLatency;
 use crate::process::ProcessResolver;
+use crate::rdns::ReverseDns;
+use crate::recorder::{Recorder, Rotation};
 use chrono::Local;
-use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
-    execute,
-    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
-};
-use ratatui::{Terminal, backend::CrosstermBackend, widgets::ListState};
-use std::collections::HashMap;
+use crossterm::event::{self, Event, KeyCode, MouseButton, MouseEventKind};
+use ratatui::widgets::ListState;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::io::{self, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex, mpsc};
 use std::thread;
 use std::time::{Duration, Instant};
 
+/// How often a vanished capture interface is retried.
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Packets kept in the feed; the oldest are dropped beyond this.
+const FEED_CAPACITY: usize = 1000;
+
+/// Packets held back while paused. Past this the channel fills up and live capture
+/// drops what it can't queue, counted in the footer.
+const PAUSE_BACKLOG: usize = 100_000;
+
+/// (source, dest, protocol label, app name) — one row of the Connections tab. Both
+/// directions of a conversation share the row; see [`is_outbound`] for which end
+/// is the source.
+pub type ConnectionKey = (String, String, String, String);
+
+/// Running totals for one Connections row.
+#[derive(Debug, Default, Clone)]
+pub struct ConnectionStats {
+    pub bytes: u64,
+    /// Bytes sent by the row's source (↑) and by its destination (↓)
+    pub bytes_out: u64,
+    pub bytes_in: u64,
+    pub packets: u64,
+    /// Distinct flows (port pairs) counted in this row; shown when ephemeral ports are folded
+    pub flows: u64,
+    /// Oldest packet's capture time, for how long the session has been active
+    pub first_seen: Option<Instant>,
+    /// Newest packet's capture time; `--max-mem` evicts the longest-idle rows first
+    pub last_seen: Option<Instant>,
+    /// Remote end's country, from the first packet that had one (`--geoip`)
+    pub country: Option<String>,
+}
+
+impl ConnectionStats {
+    pub fn record(&mut self, packet: &PacketData, outbound: bool) {
+        self.bytes += packet.length as u64;
+        if outbound {
+            self.bytes_out += packet.length as u64;
+        } else {
+            self.bytes_in += packet.length as u64;
+        }
+        self.packets += 1;
+        self.first_seen.get_or_insert(packet.timestamp);
+        self.last_seen = Some(packet.timestamp);
+        if self.country.is_none() {
+            self.country.clone_from(&packet.country);
+        }
+    }
+
+    /// Folds another row's totals in, for re-grouping at a coarser granularity.
+    pub fn merge(&mut self, other: &ConnectionStats) {
+        self.bytes += other.bytes;
+        self.bytes_out += other.bytes_out;
+        self.bytes_in += other.bytes_in;
+        self.packets += other.packets;
+        self.flows += other.flows;
+        // `None` sorts first, so `min` alone would lose a known start time
+        self.first_seen = match (self.first_seen, other.first_seen) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self.last_seen = self.last_seen.max(other.last_seen);
+        if self.country.is_none() {
+            self.country.clone_from(&other.country);
+        }
+    }
+}
+
+/// Finest grouping kept: the default key plus the TCP/UDP ports, so the Connections
+/// tab can be re-grouped at any [`KeyGranularity`] without losing history.
+pub type FlowKey = (ConnectionKey, Option<(u16, u16)>);
+
+/// A frame as read off the capture handle, on its way to the parser thread.
+struct RawFrame {
+    data: Vec<u8>,
+    /// pcap record time in µs since the epoch
+    ts: i64,
+    /// When it was read, or when it was due under --replay-speed
+    captured_at: Instant,
+}
+
+/// How much of a packet's addressing the Connections tab groups by.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeyGranularity {
+    /// Host pair only
+    Host,
+    /// Host pair, protocol label and app (the default)
+    Proto,
+    /// ...plus the destination port
+    Port,
+    /// ...plus both ports: one row per transport flow
+    Flow,
+}
+
+impl KeyGranularity {
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "host" => KeyGranularity::Host,
+            "proto" => KeyGranularity::Proto,
+            "port" => KeyGranularity::Port,
+            "flow" => KeyGranularity::Flow,
+            _ => return None,
+        })
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            KeyGranularity::Host => KeyGranularity::Proto,
+            KeyGranularity::Proto => KeyGranularity::Port,
+            KeyGranularity::Port => KeyGranularity::Flow,
+            KeyGranularity::Flow => KeyGranularity::Host,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            KeyGranularity::Host => "host",
+            KeyGranularity::Proto => "host+proto",
+            KeyGranularity::Port => "host+proto+port",
+            KeyGranularity::Flow => "5-tuple",
+        }
+    }
+}
+
+/// Whether `packet` travels from the end its Connections row is keyed by. Rows start
+/// at this machine when one side is local; between other hosts (or on loopback) the
+/// client, i.e. the higher port, comes first, and failing that the lower address.
+pub fn is_outbound(packet: &PacketData, local: &HashSet<String>) -> bool {
+    match (local.contains(&packet.source), local.contains(&packet.dest)) {
+        (true, false) => true,
+        (false, true) => false,
+        _ => match packet.ports {
+            Some((src_port, dst_port)) if src_port != dst_port => src_port > dst_port,
+            _ => packet.source <= packet.dest,
+        },
+    }
+}
+
+/// With `unmap_v4`, IPv4-mapped IPv6 endpoints are keyed by their IPv4 form. Replies
+/// (`outbound == false`) are keyed from the other end, so both directions meet.
+pub fn flow_key(packet: &PacketData, unmap_v4: bool, outbound: bool) -> FlowKey {
+    let host = |addr: &String| {
+        if unmap_v4 {
+            unmap_ipv4(addr)
+        } else {
+            addr.clone()
+        }
+    };
+    let (source, dest, ports) = if outbound {
+        (&packet.source, &packet.dest, packet.ports)
+    } else {
+        (
+            &packet.dest,
+            &packet.source,
+            packet.ports.map(|(src, dst)| (dst, src)),
+        )
+    };
+    (
+        (
+            host(source),
+            host(dest),
+            packet.proto_label.clone(),
+            packet.app_name.clone(),
+        ),
+        ports,
+    )
+}
+
+/// Lowest port treated as a client's throwaway source port (Linux starts its range
+/// here; IANA and Windows at 49152).
+const EPHEMERAL_PORT_START: u16 = 32768;
+
+/// The Connections row a flow belongs to at `granularity`. With `fold_ephemeral`, an
+/// ephemeral port facing a well-known one is written as `*`, so a client's many
+/// connections to one service share a row.
+pub fn granular_key(
+    flow: &FlowKey,
+    granularity: KeyGranularity,
+    fold_ephemeral: bool,
+) -> ConnectionKey {
+    let ((source, dest, proto, app), ports) = flow;
+    let folded = |port: u16, other: u16| {
+        fold_ephemeral && port >= EPHEMERAL_PORT_START && other < EPHEMERAL_PORT_START
+    };
+    let with_port = |addr: &str, port: u16, other: u16| {
+        let port = if folded(port, other) {
+            "*".to_string()
+        } else {
+            port.to_string()
+        };
+        if addr.contains(':') {
+            format!("[{}]:{}", addr, port)
+        } else {
+            format!("{}:{}", addr, port)
+        }
+    };
+    match (granularity, ports) {
+        (KeyGranularity::Host, _) => (source.clone(), dest.clone(), "*".into(), "*".into()),
+        (KeyGranularity::Port, Some((src_port, dst_port))) => (
+            source.clone(),
+            with_port(dest, *dst_port, *src_port),
+            proto.clone(),
+            app.clone(),
+        ),
+        (KeyGranularity::Flow, Some((src_port, dst_port))) => (
+            with_port(source, *src_port, *dst_port),
+            with_port(dest, *dst_port, *src_port),
+            proto.clone(),
+            app.clone(),
+        ),
+        _ => flow.0.clone(),
+    }
+}
+
+pub fn connection_key(
+    packet: &PacketData,
+    unmap_v4: bool,
+    granularity: KeyGranularity,
+    local: &HashSet<String>,
+) -> ConnectionKey {
+    let outbound = is_outbound(packet, local);
+    granular_key(&flow_key(packet, unmap_v4, outbound), granularity, false)
+}
+
+/// Rebuilds the Connections rows from the per-flow totals.
+fn regroup(
+    flows: &HashMap<FlowKey, ConnectionStats>,
+    granularity: KeyGranularity,
+    fold_ephemeral: bool,
+) -> HashMap<ConnectionKey, ConnectionStats> {
+    let mut connections: HashMap<ConnectionKey, ConnectionStats> = HashMap::new();
+    for (flow, stats) in flows {
+        connections
+            .entry(granular_key(flow, granularity, fold_ephemeral))
+            .or_default()
+            .merge(stats);
+    }
+    connections
+}
+
 #[derive(PartialEq, Debug)]
 pub enum InputMode {
     Normal,
     Search,
+    /// Waiting for y/n on the clear in `pending_clear`
+    Confirm,
 }
 
 #[derive(PartialEq, Debug, Clone, Copy)]
@@ -29,215 +304,752 @@ pub enum Tab {
     Connections,
 }
 
+impl Tab {
+    fn next(self) -> Self {
+        match self {
+            Tab::Feed => Tab::Connections,
+            Tab::Connections => Tab::Feed,
+        }
+    }
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // 1. Device Selection
-    let devices = pcap::Device::list()?;
-    println!("--- Available Interfaces ---");
-    for (i, d) in devices.iter().enumerate() {
-        println!("[{}] {}", i, d.name);
+    let args = Args::parse()?;
+    let keymap = KeyMap::load(args.keymap.as_deref())?;
+    if let Some(path) = &args.log {
+        logging::init(path)?;
+    }
+    oui::init(args.oui_file.as_deref())?;
+    geoip::init(args.geoip.as_deref())?;
+
+    if let Some((a, b)) = &args.diff {
+        return diff::run(a, b, &keymap, args.anonymize);
+    }
+    if args.list_interfaces {
+        print_devices(&pcap::Device::list()?);
+        return Ok(());
+    }
+
+    // 1. Capture Source: --interface, an interface picked interactively, or --read
+    let (mut cap, mut source_info): (pcap::Capture<dyn pcap::Activated>, SourceInfo) =
+        match &args.read {
+            Some(source) => {
+                let cap = open_read(source)?;
+                let info = SourceInfo {
+                    name: if source == Path::new("-") {
+                        "stdin".into()
+                    } else {
+                        source.display().to_string()
+                    },
+                    linktype: cap.get_datalink(),
+                    addresses: Vec::new(),
+                    modes: None,
+                    filter: None,
+                };
+                (cap.into(), info)
+            }
+            None => {
+                let (cap, info) = open_live(&args)?;
+                (cap.into(), info)
+            }
+        };
+    if let Some(expr) = &args.filter {
+        cap.filter(expr, true)
+            .map_err(|e| format!("Invalid capture filter \"{}\": {}", expr, e))?;
+        source_info.filter = Some(expr.clone());
+    }
+    let linktype = cap.get_datalink();
+    // Real addresses, for orienting packets before the anonymizer rewrites them
+    let captured_local: HashSet<String> = source_info.addresses.iter().cloned().collect();
+    let mut anonymizer = args.anonymize.then(Anonymizer::new);
+    if let Some(anonymizer) = anonymizer.as_mut() {
+        for addr in &mut source_info.addresses {
+            *addr = anonymizer.rewrite(addr);
+        }
     }
-    print!("Select Interface Number: ");
-    io::stdout().flush()?;
-
-    let mut input = String::new();
-    io::stdin().read_line(&mut input)?;
-    let index: usize = input.trim().parse().map_err(|_| "Invalid selection")?;
-    let selected_device = devices[index].clone();
-
-    // 2. Terminal Setup
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
-
-    // 3. Shared State & Channels
-    let (tx, rx) = mpsc::channel::<PacketData>();
+    let source_summary = source_info.summary();
+    // Endpoints that are this machine; everything else counts as a remote host
+    let local_addresses: HashSet<String> = source_info.addresses.iter().cloned().collect();
+
+    // 2. Shared State & Channels
+    // Both bounded, so a stalled parser or UI can't make a queue grow until memory
+    // runs out: capture -> parser carries raw frames, parser -> UI parsed packets
+    let (frame_tx, frame_rx) = mpsc::sync_channel::<RawFrame>(args.channel_capacity);
+    let (tx, rx) = mpsc::sync_channel::<PacketData>(args.channel_capacity);
     let resolver: Arc<Mutex<ProcessResolver>> = Arc::new(Mutex::new(ProcessResolver::new()));
-    let save_file: Arc<Mutex<Option<pcap::Savefile>>> = Arc::new(Mutex::new(None));
+    let save_file: Arc<Mutex<Option<Recorder>>> = Arc::new(Mutex::new(None));
+    let capture_stats: Arc<Mutex<Option<pcap::Stat>>> = Arc::new(Mutex::new(None));
+    // Why the live interface stopped delivering, while the capture thread tries to reopen it
+    let capture_down: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+    // Bytes captured since the last throughput tick, counted on arrival so a stalled
+    // UI loop doesn't shift them into a later bucket
+    let arrived_bytes = Arc::new(AtomicU64::new(0));
+    // Set once a --read file (or stdin stream) has been read to the end
+    let input_ended = Arc::new(AtomicBool::new(false));
+    // Live frames discarded because the parser's queue was full
+    let frames_dropped = Arc::new(AtomicU64::new(0));
+    // Live packets discarded because the queue to the UI was full
+    let queue_dropped = Arc::new(AtomicU64::new(0));
+    // Discovery chatter passes to the UI only while this is set; `M` flips it
+    let show_noise = Arc::new(AtomicBool::new(args.show_multicast));
+    let noise_hidden = Arc::new(AtomicU64::new(0));
+    // The search promoted with `f`, applied in the parser so discarded packets are
+    // never charted; the UI keeps its own copy for what was already queued
+    let promoted_filter: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+    let capture_filter_dropped = Arc::new(AtomicU64::new(0));
 
     // App state
     let mut active_tab = Tab::Feed;
-    let mut connections: HashMap<(String, String, String, String), u64> = HashMap::new();
+    let mut connections: HashMap<ConnectionKey, ConnectionStats> = HashMap::new();
+    let mut flows: HashMap<FlowKey, ConnectionStats> = HashMap::new(); // Per-port totals behind `connections`
+    let mut granularity = args.granularity;
+    let mut fold_ephemeral = false; // Collapse client ephemeral ports into one row per service
+    let mut port_counts: HashMap<u16, u64> = HashMap::new(); // Packets per destination port
+    let mut app_bytes: HashMap<String, u64> = HashMap::new(); // Bytes per attributed process
+    let mut proto_bytes: HashMap<String, u64> = HashMap::new(); // Bytes per protocol label
+    let mut baseline: Option<HashSet<ConnectionKey>> = None; // Keys present at the last snapshot
     let mut feed_list_state = ListState::default();
     let mut connections_list_state = ListState::default();
-    let mut local_packets: Vec<PacketData> = Vec::new();
+    let mut local_packets: VecDeque<PacketData> = VecDeque::with_capacity(FEED_CAPACITY + 1);
     let mut input_mode = InputMode::Normal;
+    let mut pending_clear: Option<Action> = None; // ClearFeed/ClearConnections awaiting y/n
     let mut filter_text = String::new();
+    let mut app_filter: Option<String> = None; // Exact app_name match, composes with filter_text
+    let mut capture_filter: Option<String> = None; // Lowercased search text; non-matching packets are discarded
+    let mut enabled_chips: HashSet<&str> = ui::CHIPS.iter().copied().collect(); // Feed quick filters
     let mut is_paused = false;
+    let mut paused_backlog: VecDeque<PacketData> = VecDeque::new(); // Arrived while paused, applied on resume
+    let mut backlog_dropped: u64 = 0; // Live packets discarded during this pause because the backlog was full
     let mut is_saving = false;
     let mut selected_spike_index: Option<usize> = None; // Initialize here
+    let mut chart_scale = ui::ChartScale::Auto;
+    let mut rate_unit = ui::RateUnit::Bytes;
+    let mut connection_sort = ui::ConnectionSort::default();
+    let mut show_length = true;
+    let mut show_ports = false;
+    let mut show_apps = false;
+    let mut show_protocols = false;
+    let mut show_slow_dns = false;
+    let mut dns_latency = DnsLatency::new();
+    let mut show_hostnames = false;
+    let mut reverse_dns: Option<ReverseDns> = None; // Lookup thread, started the first time 'r' is pressed
+    let mut spike_absolute_time = false; // Spike analysis shows wall-clock time instead of "Ns ago"
+    let mut show_detail = true; // Only consulted by the compact (narrow terminal) layout
     // Throughput tracking
-    let mut throughput_history: Vec<u64> = vec![0; 200];
+    let mut throughput_history: VecDeque<u64> = VecDeque::from(vec![0; args.history_seconds]);
     let mut bytes_current_second = 0;
+    let mut throughput_peak: u64 = 0; // Busiest second of the session, beyond the chart's window
+    let mut new_conns_current_second: u64 = 0; // First-seen connection keys this tick
+    let mut new_conn_rate: u64 = 0; // ...and in the last complete second
+    let mut last_bucket_at = Local::now(); // Wall-clock end of the newest throughput bucket
+    let mut throughput_log = match &args.throughput_log {
+        Some(path) => Some(
+            ThroughputLog::open(path)
+                .map_err(|e| format!("Cannot open {}: {}", path.display(), e))?,
+        ),
+        None => None,
+    };
+    let mut page_rows: usize = 1; // Rows the active list showed on the last draw, for PageUp/PageDown
+    let mut status_message: Option<(String, Instant)> = None; // Footer notice, e.g. after an export
+    let mut detail_view = ui::DetailView::Details;
+    let mut pinned_packet: Option<PacketData> = None; // Inspector subject frozen with PinInspector
+    let mut capture_status: Option<String> = None; // Interface error while reconnecting
+    let mut process_warning: Option<String> = None; // Set once if procfs turns out to be unusable
+    let mut remote_hosts: usize = 0; // Distinct non-local addresses in `flows`, refreshed each tick
+    let mut memory_used: usize = 0; // Feed + connections estimate, refreshed each tick under --max-mem
+    let mut retained_bytes: usize = 0; // Sum of PacketData::raw lengths currently held
     let mut last_tick = Instant::now();
     let mut pause_time: Option<Instant> = None;
     let mut frozen_history: Vec<u64> = Vec::new(); // Store the chart state here when paused
-    // 4. Capture Thread
-    let resolver_cap = Arc::clone(&resolver);
+    let started = Instant::now();
+    let deadline = args.duration.map(|d| started + d);
+    let mut total_packets: u64 = 0;
+    let mut total_bytes: u64 = 0;
+    let frame_interval = args
+        .fps
+        .map(|fps| Duration::from_secs_f64(1.0 / fps as f64));
+    let mut last_draw = Instant::now();
+    // 3. Capture Thread: only reads frames, so next_packet() keeps up with the wire
     let save_file_capture = Arc::clone(&save_file);
-
-    // FIX: Clone the device so the thread can own one copy while main() keeps the other
-    let device_for_thread = selected_device.clone();
+    let stats_capture = Arc::clone(&capture_stats);
+    let frames_dropped_capture = Arc::clone(&frames_dropped);
+    let ended_capture = Arc::clone(&input_ended);
+    // Files can wait for the UI; a live interface can't, so its overflow is dropped
+    let lossless = args.read.is_some();
+    let down_capture = Arc::clone(&capture_down);
+    // Only a live interface can come back; files and stdin just end
+    let reconnect = match (&args.read, source_info.modes) {
+        (None, Some(modes)) => Some((source_info.name.clone(), modes, args.filter.clone())),
+        _ => None,
+    };
+    let replay_speed = args.read.as_ref().and(args.replay_speed);
 
     thread::spawn(move || {
-        let mut cap = pcap::Capture::from_device(device_for_thread)
-            .unwrap()
-            .promisc(true)
-            .immediate_mode(true)
-            .open()
-            .unwrap();
+        let mut replay_origin: Option<(i64, Instant)> = None; // First record's timestamp (µs) and when it was replayed
+        let mut last_stats = Instant::now();
+        let mut dropped_so_far = 0;
 
-        let mut last_refresh = Instant::now();
+        loop {
+            // Poll kernel/interface counters every second so drops are visible in the UI
+            if last_stats.elapsed() >= Duration::from_secs(1) {
+                match cap.stats() {
+                    Ok(stat) => {
+                        if stat.dropped > dropped_so_far {
+                            log::warn!(
+                                "kernel dropped {} packets ({} total)",
+                                stat.dropped - dropped_so_far,
+                                stat.dropped
+                            );
+                            dropped_so_far = stat.dropped;
+                        }
+                        if let Ok(mut guard) = stats_capture.lock() {
+                            *guard = Some(stat);
+                        }
+                    }
+                    Err(e) => log::debug!("pcap stats unavailable: {}", e),
+                }
+                last_stats = Instant::now();
+            }
 
-        while let Ok(packet) = cap.next_packet() {
-            // Log to file if active
+            let packet = match cap.next_packet() {
+                Ok(packet) => packet,
+                // End of a --read file, or the stdin stream was closed
+                Err(pcap::Error::NoMorePackets) => {
+                    log::info!("capture source reached end of input");
+                    ended_capture.store(true, Ordering::Relaxed);
+                    break;
+                }
+                Err(pcap::Error::TimeoutExpired) => continue,
+                // Wi-Fi dropped or a USB NIC was unplugged: keep what was captured and
+                // retry the device until it reappears
+                Err(e) if reconnect.is_some() => {
+                    let (name, modes, filter) = reconnect.as_ref().unwrap();
+                    log::warn!("capture on {} failed: {}; reconnecting", name, e);
+                    if let Ok(mut guard) = down_capture.lock() {
+                        *guard = Some(e.to_string());
+                    }
+                    loop {
+                        thread::sleep(RECONNECT_INTERVAL);
+                        match reopen_device(name, *modes, linktype, filter.as_deref()) {
+                            Ok(reopened) => {
+                                cap = reopened.into();
+                                break;
+                            }
+                            Err(e) => log::debug!("reopening {} failed: {}", name, e),
+                        }
+                    }
+                    log::info!("capture on {} resumed", name);
+                    dropped_so_far = 0;
+                    if let Ok(mut guard) = down_capture.lock() {
+                        *guard = None;
+                    }
+                    continue;
+                }
+                Err(e) => {
+                    log::error!("capture stopped: {}", e);
+                    break;
+                }
+            };
 
-            if let Ok(mut guard) = save_file_capture.lock() {
-                if let Some(file) = guard.as_mut() {
-                    file.write(&packet);
+            // --replay-speed: hold each record back until its recorded offset (scaled)
+            // has passed, so the feed and chart unfold as they did on the wire
+            let ts = packet.header.ts.tv_sec as i64 * 1_000_000 + packet.header.ts.tv_usec as i64;
+            let mut captured_at = Instant::now();
+            if let Some(speed) = replay_speed {
+                let (first_ts, replay_start) = *replay_origin.get_or_insert((ts, Instant::now()));
+                let offset = Duration::from_micros(((ts - first_ts).max(0) as f64 / speed) as u64);
+                let due = replay_start + offset;
+                if let Some(wait) = due.checked_duration_since(Instant::now()) {
+                    thread::sleep(wait);
                 }
+                captured_at = due;
+            }
+
+            // Log to file if active; --rotate-mb/--rotate-minutes/--ring-files rotation
+            // happens in here
+            if let Ok(mut guard) = save_file_capture.lock()
+                && let Some(recorder) = guard.as_mut()
+            {
+                recorder.write(&packet);
             }
 
+            let frame = RawFrame {
+                data: packet.data.to_vec(),
+                ts,
+                captured_at,
+            };
+            if lossless {
+                if frame_tx.send(frame).is_err() {
+                    break;
+                }
+            } else if let Err(e) = frame_tx.try_send(frame) {
+                match e {
+                    mpsc::TrySendError::Full(_) => {
+                        frames_dropped_capture.fetch_add(1, Ordering::Relaxed);
+                    }
+                    mpsc::TrySendError::Disconnected(_) => break,
+                }
+            }
+        }
+    });
+
+    // 3b. Parser Thread: decoding, process attribution and anonymizing. One thread,
+    // because ParseState (fragment reassembly, TCP window scales, RTP jitter) has to
+    // see each flow's frames in order
+    let resolver_cap = Arc::clone(&resolver);
+    let arrived_capture = Arc::clone(&arrived_bytes);
+    let queue_dropped_capture = Arc::clone(&queue_dropped);
+    let show_noise_capture = Arc::clone(&show_noise);
+    let noise_hidden_capture = Arc::clone(&noise_hidden);
+    let filter_capture = Arc::clone(&promoted_filter);
+    let filter_dropped_capture = Arc::clone(&capture_filter_dropped);
+    let retain_raw = args.retain_bytes.is_some();
+    let mut deduplicator = args.dedup.then(Deduplicator::new);
+
+    thread::spawn(move || {
+        let mut last_refresh = Instant::now();
+        let mut parse_state = ParseState::new();
+
+        // Ends once the capture thread stops and its queue is drained
+        for frame in frame_rx {
             // Refresh process mappings every 2s
             if last_refresh.elapsed() > Duration::from_secs(2) {
                 if let Ok(mut res) = resolver_cap.lock() {
+                    let started = Instant::now();
                     res.refresh();
+                    log::debug!("process map refreshed in {:?}", started.elapsed());
                 }
                 last_refresh = Instant::now();
             }
 
-            let mut app_name = String::from("Unknown");
-
-            // Try SLL first (for 'any' device) then Ethernet
-            let parsed_headers = etherparse::SlicedPacket::from_linux_sll(&packet.data)
-                .or_else(|_| etherparse::SlicedPacket::from_ethernet(&packet.data));
+            let mut parsed = parse_packet_full(&frame.data, linktype, &mut parse_state);
+            // The record's own time, so a --read file shows when it was captured rather
+            // than when it was opened; paced replays also keep the exact spacing
+            parsed.time_label = capture::time_label(frame.ts);
+            parsed.captured_micros = frame.ts;
+            parsed.timestamp = frame.captured_at;
 
-            if let Ok(p) = parsed_headers {
-                if let Some(t) = p.transport {
-                    let (src, dst) = match t {
-                        etherparse::TransportSlice::Tcp(s) => {
-                            (s.source_port(), s.destination_port())
-                        }
-                        etherparse::TransportSlice::Udp(s) => {
-                            (s.source_port(), s.destination_port())
-                        }
-                        _ => (0, 0),
-                    };
-
-                    if let Ok(res_guard) = resolver_cap.lock() {
-                        app_name = res_guard.resolve_port(src);
-                        if app_name == "Unknown" && dst > 0 {
-                            app_name = res_guard.resolve_port(dst);
-                        }
-                    }
+            // Ports come from the parsed packet so reassembled fragments get attributed too
+            if let Some((src, dst)) = parsed.ports
+                && let Ok(res_guard) = resolver_cap.lock()
+            {
+                parsed.app_name = res_guard.resolve_port(src);
+                if parsed.app_name == "Unknown" && dst > 0 {
+                    parsed.app_name = res_guard.resolve_port(dst);
                 }
             }
-
-            if let Some(parsed) = parse_packet_full(&packet.data, app_name) {
-                if parsed.proto_label == "SSDP"
-                    || parsed.dest.contains("239.255.255.250")
-                    || parsed.dest.contains("ff05::c")
-                // Catch the IPv6 version too!
-                {
-                    continue; // Skip this packet and move to the next one
+            parsed.country = geoip::remote_country(&parsed, is_outbound(&parsed, &captured_local));
+            if !show_noise_capture.load(Ordering::Relaxed) && noise::is_noise(&parsed) {
+                noise_hidden_capture.fetch_add(1, Ordering::Relaxed);
+                continue;
+            }
+            if let Some(anonymizer) = anonymizer.as_mut() {
+                anonymizer.apply(&mut parsed);
+            }
+            if let Ok(filter) = filter_capture.lock()
+                && let Some(text) = filter.as_deref()
+                && !ui::matches_text(&parsed, text)
+            {
+                filter_dropped_capture.fetch_add(1, Ordering::Relaxed);
+                continue;
+            }
+            if retain_raw {
+                parsed.raw = Some(frame.data);
+            }
+            // Mirrored copies still go to the UI to be credited to their original, but
+            // their bytes are only counted once
+            parsed.duplicate = deduplicator
+                .as_mut()
+                .is_some_and(|dedup| dedup.is_duplicate(parsed.fingerprint, parsed.timestamp));
+            if !parsed.duplicate {
+                arrived_capture.fetch_add(parsed.length as u64, Ordering::Relaxed);
+            }
+            if lossless {
+                if tx.send(parsed).is_err() {
+                    break;
                 }
-                let _ = tx.send(parsed);
+            } else if let Err(mpsc::TrySendError::Full(_)) = tx.try_send(parsed) {
+                queue_dropped_capture.fetch_add(1, Ordering::Relaxed);
             }
         }
     });
 
+    // --headless: same pipeline, but packets go to stdout instead of the TUI
+    if args.headless {
+        return run_headless(&rx, deadline, started);
+    }
+
+    // 4. Terminal Setup; from here on an error or panic still restores the terminal
+    let mut terminal = screen::enter(true)?;
+
     // 5. UI Loop
     loop {
+        // --duration: leave exactly as if the user had pressed quit
+        if deadline.is_some_and(|d| Instant::now() >= d) {
+            break;
+        }
         let mut received_new = false;
 
-        // Handle incoming packets
-        while let Ok(packet) = rx.try_recv() {
-            if !is_paused {
-                let key = (
-                    packet.source.clone(),
-                    packet.dest.clone(),
-                    packet.proto_label.clone(),
-                    packet.app_name.clone(),
-                );
-                *connections.entry(key).or_insert(0) += packet.length as u64;
-                bytes_current_second += packet.length as u64;
+        // Handle incoming packets. While paused they're held back rather than
+        // discarded, and resuming catches up in arrival order
+        if is_paused {
+            loop {
+                let full = paused_backlog.len() >= PAUSE_BACKLOG
+                    || args
+                        .max_mem
+                        .is_some_and(|budget| !memory::backlog_has_room(budget, memory_used));
+                // A file can wait for the resume; a live interface can't
+                if full && lossless {
+                    break;
+                }
+                let Ok(packet) = rx.try_recv() else {
+                    break;
+                };
+                if full {
+                    backlog_dropped += 1;
+                    continue;
+                }
+                if args.max_mem.is_some() {
+                    memory_used += memory::packet_size(&packet);
+                }
+                paused_backlog.push_back(packet);
+            }
+        } else {
+            let backlog = std::mem::take(&mut paused_backlog);
+            for mut packet in backlog.into_iter().chain(rx.try_iter()) {
+                // Queued (or held by a pause) before the filter was promoted
+                if let Some(text) = &capture_filter
+                    && !ui::matches_text(&packet, text)
+                {
+                    capture_filter_dropped.fetch_add(1, Ordering::Relaxed);
+                    continue;
+                }
+                // Mirrored copy: credit the original instead of counting the bytes twice
+                if packet.duplicate {
+                    if let Some(original) = local_packets
+                        .iter_mut()
+                        .rev()
+                        .find(|p| p.fingerprint == packet.fingerprint)
+                    {
+                        original.dup_count += 1;
+                    }
+                    continue;
+                }
+
+                packet.dns_latency = dns_latency.observe(&packet);
+                if show_hostnames && let Some(rdns) = reverse_dns.as_mut() {
+                    for addr in [&packet.source, &packet.dest] {
+                        if !local_addresses.contains(addr) {
+                            rdns.request(addr);
+                        }
+                    }
+                }
+                let outbound = is_outbound(&packet, &local_addresses);
+                let flow = flow_key(&packet, args.unmap_v4, outbound);
+                let key = granular_key(&flow, granularity, fold_ephemeral);
+                let flow_stats = flows.entry(flow).or_default();
+                let new_flow = flow_stats.packets == 0;
+                flow_stats.flows = 1;
+                flow_stats.record(&packet, outbound);
+                let row = connections.entry(key).or_insert_with(|| {
+                    new_conns_current_second += 1;
+                    ConnectionStats::default()
+                });
+                row.record(&packet, outbound);
+                row.flows += new_flow as u64;
+                if let Some((_, dst_port)) = packet.ports {
+                    *port_counts.entry(dst_port).or_insert(0) += 1;
+                }
+                *app_bytes.entry(packet.app_name.clone()).or_insert(0) += packet.length as u64;
+                *proto_bytes.entry(packet.proto_label.clone()).or_insert(0) += packet.length as u64;
+                if args.buffered_accounting {
+                    bytes_current_second += packet.length as u64;
+                }
+                total_packets += 1;
+                total_bytes += packet.length as u64;
 
-                local_packets.push(packet);
+                retained_bytes += packet.raw.as_ref().map_or(0, Vec::len);
+                local_packets.push_back(packet);
                 received_new = true;
-                if local_packets.len() > 1000 {
-                    local_packets.remove(0);
+                if local_packets.len() > FEED_CAPACITY
+                    && let Some(evicted) = local_packets.pop_front()
+                {
+                    retained_bytes -= evicted.raw.map_or(0, |raw| raw.len());
+                }
+                if let Some(budget) = args.retain_bytes {
+                    enforce_raw_budget(&mut local_packets, &mut retained_bytes, budget);
                 }
             }
         }
 
         // Update throughput graph
         if last_tick.elapsed() >= Duration::from_secs(1) {
-            throughput_history.push(bytes_current_second);
-            if throughput_history.len() > 200 {
-                throughput_history.remove(0);
+            if !args.buffered_accounting {
+                bytes_current_second = arrived_bytes.swap(0, Ordering::Relaxed);
+            }
+            throughput_history.push_back(bytes_current_second);
+            throughput_peak = throughput_peak.max(bytes_current_second);
+            if throughput_history.len() > args.history_seconds {
+                throughput_history.pop_front();
+            }
+            last_bucket_at = Local::now();
+            if let Some(log) = throughput_log.as_mut()
+                && let Err(e) = log.append(last_bucket_at, bytes_current_second)
+            {
+                log::error!("throughput log write failed: {}", e);
             }
             bytes_current_second = 0;
+            new_conn_rate = new_conns_current_second;
+            new_conns_current_second = 0;
+            if let Some(budget) = args.max_mem {
+                memory_used = memory::enforce_budget(
+                    budget,
+                    &mut local_packets,
+                    &paused_backlog,
+                    &mut retained_bytes,
+                    &mut connections,
+                    &mut flows,
+                );
+            }
+            remote_hosts = flows
+                .keys()
+                .flat_map(|((source, dest, _, _), _)| [source, dest])
+                .filter(|addr| !local_addresses.contains(*addr))
+                .collect::<HashSet<_>>()
+                .len();
+            capture_status = capture_down.lock().ok().and_then(|guard| guard.clone());
+            if process_warning.is_none() {
+                process_warning = resolver
+                    .lock()
+                    .ok()
+                    .and_then(|res| res.unavailable().map(str::to_string));
+            }
             last_tick = Instant::now();
         }
 
         // Data Filtering
-        let filtered_packets: Vec<&PacketData> = local_packets
-            .iter()
-            .filter(|p| {
-                filter_text.is_empty()
-                    || p.summary
-                        .to_lowercase()
-                        .contains(&filter_text.to_lowercase())
-                    || p.app_name
-                        .to_lowercase()
-                        .contains(&filter_text.to_lowercase())
-            })
-            .collect();
+        let chart_len = if is_paused {
+            frozen_history.len()
+        } else {
+            throughput_history.len()
+        };
+        let filtered_packets = ui::visible_packets(
+            &local_packets,
+            &filter_text,
+            app_filter.as_deref(),
+            &enabled_chips,
+            selected_spike_index,
+            chart_len,
+            pause_time,
+        );
 
         // Autoscroll logic
-        if !is_paused && received_new && active_tab == Tab::Feed {
-            if !filtered_packets.is_empty() {
-                feed_list_state.select(Some(filtered_packets.len() - 1));
-            }
+        if !is_paused && received_new && active_tab == Tab::Feed && !filtered_packets.is_empty() {
+            feed_list_state.select(Some(filtered_packets.len() - 1));
         }
 
-        // Render
-        terminal.draw(|f| {
-            let chart_data = if is_paused {
-                &frozen_history
-            } else {
-                &throughput_history
-            };
-            ui::draw(
-                f,
-                active_tab,
-                &local_packets,
-                &connections,
-                &chart_data,
-                &is_paused,
-                &is_saving,
-                &filter_text,
-                &input_mode,
-                &mut feed_list_state,
-                &mut connections_list_state,
-                selected_spike_index,
-                pause_time,
-            );
-        })?;
+        let stats = capture_stats.lock().ok().and_then(|guard| *guard);
+
+        // Render (throttled by --fps; capture and aggregation above run every pass)
+        if frame_interval.is_none_or(|interval| last_draw.elapsed() >= interval) {
+            // The chart slices the history; rotating it once per frame is cheap
+            let live_history: &[u64] = throughput_history.make_contiguous();
+            let host_names = reverse_dns
+                .as_ref()
+                .filter(|_| show_hostnames)
+                .and_then(ReverseDns::names);
+            terminal.draw(|f| {
+                let chart_data = if is_paused {
+                    &frozen_history
+                } else {
+                    live_history
+                };
+                page_rows = ui::draw(
+                    f,
+                    active_tab,
+                    &local_packets,
+                    &connections,
+                    remote_hosts,
+                    baseline.as_ref(),
+                    chart_data,
+                    &is_paused,
+                    paused_backlog.len(),
+                    backlog_dropped,
+                    &is_saving,
+                    &filter_text,
+                    app_filter.as_deref(),
+                    &enabled_chips,
+                    &input_mode,
+                    &mut feed_list_state,
+                    &mut connections_list_state,
+                    selected_spike_index,
+                    pause_time,
+                    stats,
+                    show_detail,
+                    &keymap,
+                    chart_scale,
+                    rate_unit,
+                    connection_sort,
+                    show_length,
+                    show_ports.then_some(&port_counts),
+                    show_apps.then_some(&app_bytes),
+                    show_protocols.then_some(&proto_bytes),
+                    show_slow_dns.then_some(&dns_latency.slow),
+                    pending_clear,
+                    new_conn_rate,
+                    args.new_conn_alert,
+                    status_message
+                        .as_ref()
+                        .filter(|(_, at)| at.elapsed() < Duration::from_secs(3))
+                        .map(|(message, _)| message.as_str()),
+                    &source_summary,
+                    args.avg_window,
+                    throughput_peak,
+                    process_warning.as_deref(),
+                    capture_status.as_deref(),
+                    args.max_mem.map(|budget| (memory_used, budget)),
+                    filtered_packets.len(),
+                    spike_absolute_time,
+                    capture_filter
+                        .as_deref()
+                        .map(|text| (text, capture_filter_dropped.load(Ordering::Relaxed))),
+                    pinned_packet.as_ref(),
+                    detail_view,
+                    save_file
+                        .lock()
+                        .ok()
+                        .and_then(|guard| guard.as_ref().and_then(Recorder::ring_position)),
+                    args.text_limit,
+                    host_names.as_deref(),
+                    frames_dropped.load(Ordering::Relaxed),
+                    queue_dropped.load(Ordering::Relaxed),
+                    input_ended.load(Ordering::Relaxed),
+                );
+            })?;
+            last_draw = Instant::now();
+        }
 
         // Input Handling
         if event::poll(Duration::from_millis(10))? {
-            if let Event::Key(key) = event::read()? {
+            let event = event::read()?;
+            // Clicking a chip toggles it, same as its number key
+            if let Event::Mouse(mouse) = event
+                && mouse.kind == MouseEventKind::Down(MouseButton::Left)
+                && active_tab == Tab::Feed
+                && mouse.row == ui::CHIP_ROW
+                && let Some(index) = ui::chip_at(&keymap, mouse.column)
+            {
+                toggle_chip(&mut enabled_chips, index);
+                feed_list_state.select(None);
+            }
+            if let Event::Key(key) = event {
                 match input_mode {
-                    InputMode::Normal => match key.code {
-                        KeyCode::Char('q') => break,
-                        KeyCode::Char('1') => active_tab = Tab::Feed,
-                        KeyCode::Char('2') => active_tab = Tab::Connections,
-                        KeyCode::Char('/') => input_mode = InputMode::Search,
-                        KeyCode::Char(' ') => {
+                    InputMode::Normal => match keymap.action_for(&key) {
+                        Some(Action::Quit) => break,
+                        Some(Action::FeedTab) => active_tab = Tab::Feed,
+                        Some(Action::ConnectionsTab) => active_tab = Tab::Connections,
+                        Some(Action::NextTab) => active_tab = active_tab.next(),
+                        Some(Action::Search) => input_mode = InputMode::Search,
+                        Some(Action::FilterApp) => {
+                            // Toggle: drill into the selected row's app, or back out again
+                            if app_filter.is_some() {
+                                app_filter = None;
+                            } else if active_tab == Tab::Feed {
+                                app_filter = feed_list_state
+                                    .selected()
+                                    .and_then(|i| filtered_packets.get(i))
+                                    .map(|p| p.app_name.clone());
+                            } else {
+                                app_filter = connections_list_state.selected().and_then(|i| {
+                                    ui::visible_connections(
+                                        &connections,
+                                        &filter_text,
+                                        None,
+                                        connection_sort,
+                                    )
+                                    .get(i)
+                                    .map(|(key, _)| key.3.clone())
+                                });
+                            }
+                            feed_list_state.select(None);
+                            connections_list_state.select(None);
+                        }
+                        Some(Action::ToggleDetail) => show_detail = !show_detail,
+                        Some(Action::ToggleLength) => show_length = !show_length,
+                        Some(Action::TogglePorts) => show_ports = !show_ports,
+                        Some(Action::ToggleApps) => show_apps = !show_apps,
+                        Some(Action::ToggleProtocols) => show_protocols = !show_protocols,
+                        Some(Action::ToggleSlowDns) => show_slow_dns = !show_slow_dns,
+                        Some(Action::ToggleNoise) => {
+                            let shown = !show_noise.fetch_xor(true, Ordering::Relaxed);
+                            status_message = Some((
+                                if shown {
+                                    "Showing SSDP and other discovery traffic".to_string()
+                                } else {
+                                    format!(
+                                        "Hiding SSDP and other discovery traffic ({} hidden so far)",
+                                        noise_hidden.load(Ordering::Relaxed)
+                                    )
+                                },
+                                Instant::now(),
+                            ));
+                        }
+                        Some(Action::ToggleHostnames) => {
+                            let message = if args.anonymize {
+                                // Looking up pseudonyms would query unrelated real hosts
+                                "Hostnames are unavailable with --anonymize"
+                            } else {
+                                show_hostnames = !show_hostnames;
+                                if show_hostnames {
+                                    let rdns = reverse_dns.get_or_insert_with(ReverseDns::start);
+                                    for packet in &local_packets {
+                                        for addr in [&packet.source, &packet.dest] {
+                                            if !local_addresses.contains(addr) {
+                                                rdns.request(addr);
+                                            }
+                                        }
+                                    }
+                                    "Resolving remote hostnames"
+                                } else {
+                                    "Showing remote addresses"
+                                }
+                            };
+                            status_message = Some((message.to_string(), Instant::now()));
+                        }
+                        Some(Action::PromoteFilter) => {
+                            if capture_filter.is_some() {
+                                capture_filter = None;
+                                if let Ok(mut shared) = promoted_filter.lock() {
+                                    *shared = None;
+                                }
+                            } else if !filter_text.is_empty() {
+                                let text = filter_text.to_lowercase();
+                                // What's already buffered goes too, so the memory comes back now
+                                local_packets.retain(|p| {
+                                    let keep = ui::matches_text(p, &text);
+                                    if !keep {
+                                        retained_bytes -= p.raw.as_ref().map_or(0, Vec::len);
+                                    }
+                                    keep
+                                });
+                                feed_list_state.select(None);
+                                if let Ok(mut shared) = promoted_filter.lock() {
+                                    *shared = Some(text.clone());
+                                }
+                                capture_filter = Some(text);
+                                capture_filter_dropped.store(0, Ordering::Relaxed);
+                            }
+                        }
+                        Some(Action::ToggleSpikeTime) => spike_absolute_time = !spike_absolute_time,
+                        Some(Action::ToggleChip(index)) => {
+                            toggle_chip(&mut enabled_chips, index);
+                            feed_list_state.select(None);
+                        }
+                        Some(Action::TogglePause) => {
                             is_paused = !is_paused;
                             if is_paused {
-                                frozen_history = throughput_history.clone();
+                                backlog_dropped = 0;
+                                frozen_history = throughput_history.iter().copied().collect();
                                 selected_spike_index = Some(frozen_history.len().saturating_sub(1));
                                 pause_time = Some(Instant::now()); // Capture the "frozen" moment
                             } else {
@@ -245,32 +1057,45 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                                 pause_time = None;
                             }
                         }
-                        KeyCode::Char('c') => {
-                            local_packets.clear();
-                            connections.clear();
+                        Some(action @ (Action::ClearFeed | Action::ClearConnections)) => {
+                            pending_clear = Some(action);
+                            if !args.no_confirm {
+                                input_mode = InputMode::Confirm;
+                            }
                         }
-                        KeyCode::Char('w') => {
-                            let mut guard = save_file.lock().unwrap();
+                        Some(Action::ToggleSave) => {
+                            let Ok(mut guard) = save_file.lock() else {
+                                // Only a panic in the capture thread poisons this
+                                log::error!("savefile state unavailable");
+                                continue;
+                            };
                             if guard.is_some() {
                                 *guard = None;
                                 is_saving = false;
                             } else {
-                                let ts = Local::now().format("%Y-%m-%d_%H-%M-%S");
-                                let filename = format!("net-sniff_{}.pcap", ts);
-                                // FIX: Use a temporary capture handle to spawn the savefile
-                                if let Ok(tmp_cap) =
-                                    pcap::Capture::from_device(selected_device.clone())
-                                        .unwrap()
-                                        .open()
-                                {
-                                    if let Ok(file) = tmp_cap.savefile(filename) {
-                                        *guard = Some(file);
+                                match Recorder::start(
+                                    linktype,
+                                    Rotation::new(
+                                        args.rotate_bytes,
+                                        args.rotate_every,
+                                        args.ring_files,
+                                    ),
+                                ) {
+                                    Ok(recorder) => {
+                                        if let Some(path) = recorder.current_path() {
+                                            status_message = Some((
+                                                format!("Saving to {}", path.display()),
+                                                Instant::now(),
+                                            ));
+                                        }
+                                        *guard = Some(recorder);
                                         is_saving = true;
                                     }
+                                    Err(e) => log::error!("cannot create savefile: {}", e),
                                 }
                             }
                         }
-                        KeyCode::Char('j') | KeyCode::Down => {
+                        Some(Action::Down) => {
                             let state = if active_tab == Tab::Feed {
                                 &mut feed_list_state
                             } else {
@@ -282,7 +1107,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                             };
                             state.select(Some(i));
                         }
-                        KeyCode::Char('k') | KeyCode::Up => {
+                        Some(Action::Up) => {
                             let state = if active_tab == Tab::Feed {
                                 &mut feed_list_state
                             } else {
@@ -294,20 +1119,216 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                             };
                             state.select(Some(i));
                         }
-                        // In your KeyCode match block:
-                        KeyCode::Left => {
+                        Some(
+                            action @ (Action::PageDown
+                            | Action::PageUp
+                            | Action::Home
+                            | Action::End),
+                        ) => {
+                            let (state, len) = if active_tab == Tab::Feed {
+                                (&mut feed_list_state, filtered_packets.len())
+                            } else {
+                                (
+                                    &mut connections_list_state,
+                                    ui::visible_connections(
+                                        &connections,
+                                        &filter_text,
+                                        app_filter.as_deref(),
+                                        connection_sort,
+                                    )
+                                    .len(),
+                                )
+                            };
+                            // Nothing to select; Some(0) would put the detail pane
+                            // into its "has subject" layout for a row that isn't there
+                            if len == 0 {
+                                state.select(None);
+                            } else {
+                                let last = len - 1;
+                                let current = state.selected().unwrap_or(0);
+                                state.select(Some(match action {
+                                    Action::PageDown => (current + page_rows).min(last),
+                                    Action::PageUp => current.saturating_sub(page_rows),
+                                    Action::Home => 0,
+                                    _ => last,
+                                }));
+                            }
+                        }
+                        Some(Action::SpikeLeft) => {
                             if let Some(idx) = selected_spike_index {
                                 selected_spike_index = Some(idx.saturating_sub(1));
                             }
                         }
-                        KeyCode::Right => {
-                            if let Some(idx) = selected_spike_index {
-                                if idx < throughput_history.len() - 1 {
-                                    selected_spike_index = Some(idx + 1);
-                                }
+                        Some(Action::SpikeRight) => {
+                            if let Some(idx) = selected_spike_index
+                                && idx < throughput_history.len() - 1
+                            {
+                                selected_spike_index = Some(idx + 1);
                             }
                         }
-                        _ => {}
+                        Some(Action::CycleChartScale) => {
+                            chart_scale = chart_scale.next(args.chart_ceiling)
+                        }
+                        Some(Action::ToggleRateUnit) => rate_unit = rate_unit.toggle(),
+                        Some(Action::CycleSort) => {
+                            connection_sort = connection_sort.next_key();
+                            connections_list_state.select(None);
+                        }
+                        Some(Action::ReverseSort) => {
+                            connection_sort = connection_sort.reversed();
+                            connections_list_state.select(None);
+                        }
+                        Some(Action::CycleGranularity) => {
+                            granularity = granularity.next();
+                            connections = regroup(&flows, granularity, fold_ephemeral);
+                            // Old keys don't line up with the new rows
+                            baseline = None;
+                            connections_list_state.select(None);
+                            status_message = Some((
+                                format!("Grouping connections by {}", granularity.name()),
+                                Instant::now(),
+                            ));
+                        }
+                        Some(Action::ToggleEphemeralFold) => {
+                            fold_ephemeral = !fold_ephemeral;
+                            connections = regroup(&flows, granularity, fold_ephemeral);
+                            baseline = None;
+                            connections_list_state.select(None);
+                            status_message = Some((
+                                if fold_ephemeral {
+                                    match granularity {
+                                        KeyGranularity::Port | KeyGranularity::Flow => {
+                                            "Folding ephemeral client ports".to_string()
+                                        }
+                                        // Nothing to fold until ports are part of the key
+                                        _ => "Folding ephemeral client ports (group by port or flow to see it)".to_string(),
+                                    }
+                                } else {
+                                    "Showing every client port".to_string()
+                                },
+                                Instant::now(),
+                            ));
+                        }
+                        Some(Action::CycleDetailView) => {
+                            detail_view = detail_view.next(args.retain_bytes.is_some());
+                        }
+                        Some(Action::PinInspector) => {
+                            let selected = feed_list_state
+                                .selected()
+                                .filter(|_| active_tab == Tab::Feed)
+                                .and_then(|i| filtered_packets.get(i));
+                            let same = |a: &PacketData, b: &PacketData| {
+                                a.fingerprint == b.fingerprint && a.timestamp == b.timestamp
+                            };
+                            // Pinning the pinned packet (or nothing) lets the inspector follow again
+                            let message = match (selected, &pinned_packet) {
+                                (Some(packet), Some(pinned)) if !same(packet, pinned) => {
+                                    pinned_packet = Some((*packet).clone());
+                                    "Inspector re-pinned to the selected packet"
+                                }
+                                (Some(packet), None) => {
+                                    pinned_packet = Some((*packet).clone());
+                                    "Inspector pinned; the selection no longer changes it"
+                                }
+                                _ => {
+                                    pinned_packet = None;
+                                    "Inspector follows the selection"
+                                }
+                            };
+                            status_message = Some((message.to_string(), Instant::now()));
+                        }
+                        Some(Action::ExportPacket) => {
+                            let selected = feed_list_state
+                                .selected()
+                                .filter(|_| active_tab == Tab::Feed)
+                                .and_then(|i| filtered_packets.get(i));
+                            let message = match selected {
+                                None => "Select a packet in the feed to export it".to_string(),
+                                Some(packet) => {
+                                    let filename = format!(
+                                        "net-sniff_packet_{}.txt",
+                                        Local::now().format("%Y-%m-%d_%H-%M-%S")
+                                    );
+                                    match write_packet_txt(Path::new(&filename), packet) {
+                                        Ok(()) => format!("Packet written to {}", filename),
+                                        Err(e) => {
+                                            log::error!("cannot write {}: {}", filename, e);
+                                            format!("Export failed: {}", e)
+                                        }
+                                    }
+                                }
+                            };
+                            status_message = Some((message, Instant::now()));
+                        }
+                        Some(Action::ExportFeed) => {
+                            let filename = format!(
+                                "net-sniff_feed_{}.jsonl",
+                                Local::now().format("%Y-%m-%d_%H-%M-%S")
+                            );
+                            // The rows the feed is showing, filters and spike window applied
+                            let message =
+                                match write_feed_jsonl(Path::new(&filename), &filtered_packets) {
+                                    Ok(()) => format!(
+                                        "{} packets written to {}",
+                                        filtered_packets.len(),
+                                        filename
+                                    ),
+                                    Err(e) => {
+                                        log::error!("cannot write {}: {}", filename, e);
+                                        format!("Export failed: {}", e)
+                                    }
+                                };
+                            status_message = Some((message, Instant::now()));
+                        }
+                        Some(Action::SnapshotConnections) => {
+                            baseline = Some(connections.keys().cloned().collect());
+                            status_message = Some((
+                                format!("Baseline: {} connections", connections.len()),
+                                Instant::now(),
+                            ));
+                        }
+                        Some(Action::ExportThroughput) => {
+                            let filename = format!(
+                                "net-sniff_throughput_{}.csv",
+                                Local::now().format("%Y-%m-%d_%H-%M-%S")
+                            );
+                            let message = match write_throughput_csv(
+                                Path::new(&filename),
+                                throughput_history.make_contiguous(),
+                                last_bucket_at,
+                            ) {
+                                Ok(()) => format!("Throughput written to {}", filename),
+                                Err(e) => {
+                                    log::error!("cannot write {}: {}", filename, e);
+                                    format!("Export failed: {}", e)
+                                }
+                            };
+                            status_message = Some((message, Instant::now()));
+                        }
+                        Some(Action::ExportConnections) => {
+                            let filename = format!(
+                                "net-sniff_connections_{}.csv",
+                                Local::now().format("%Y-%m-%d_%H-%M-%S")
+                            );
+                            // Same rows, same order as the Connections tab shows
+                            let rows = ui::visible_connections(
+                                &connections,
+                                &filter_text,
+                                app_filter.as_deref(),
+                                connection_sort,
+                            );
+                            let message = match write_connections_csv(Path::new(&filename), &rows) {
+                                Ok(()) => {
+                                    format!("{} connections written to {}", rows.len(), filename)
+                                }
+                                Err(e) => {
+                                    log::error!("cannot write {}: {}", filename, e);
+                                    format!("Export failed: {}", e)
+                                }
+                            };
+                            status_message = Some((message, Instant::now()));
+                        }
+                        None => {}
                     },
                     InputMode::Search => match key.code {
                         KeyCode::Enter | KeyCode::Esc => input_mode = InputMode::Normal,
@@ -317,17 +1338,232 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                         }
                         _ => {}
                     },
+                    InputMode::Confirm => {
+                        // Anything but 'y' cancels
+                        if !matches!(key.code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                            pending_clear = None;
+                        }
+                        input_mode = InputMode::Normal;
+                    }
+                }
+
+                // Confirmed (or --no-confirm) clears happen once we're back in Normal mode
+                if input_mode == InputMode::Normal
+                    && let Some(target) = pending_clear.take()
+                {
+                    if target == Action::ClearFeed {
+                        local_packets.clear();
+                        retained_bytes = 0;
+                        feed_list_state.select(None);
+                        pinned_packet = None;
+                    } else {
+                        connections.clear();
+                        flows.clear();
+                        port_counts.clear();
+                        app_bytes.clear();
+                        proto_bytes.clear();
+                        dns_latency.clear();
+                        connections_list_state.select(None);
+                    }
                 }
             }
         }
     }
 
     // Cleanup
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
+    drop(terminal);
+
+    // The capture thread never exits on its own, so close the savefile here to flush it
+    if let Some(mut recorder) = save_file.lock().ok().and_then(|mut guard| guard.take()) {
+        recorder.flush()?;
+    }
+
+    println!(
+        "Captured {} packets ({} bytes) in {}s",
+        total_packets,
+        total_bytes,
+        started.elapsed().as_secs()
+    );
+    Ok(())
+}
+
+/// The interface list shared by `--list` and the startup prompt.
+fn print_devices(devices: &[pcap::Device]) {
+    println!("--- Available Interfaces ---");
+    for (i, d) in devices.iter().enumerate() {
+        match &d.desc {
+            Some(desc) => println!("[{}] {} ({})", i, d.name, desc),
+            None => println!("[{}] {}", i, d.name),
+        }
+    }
+}
+
+/// Prompts for an interface (and datalink, unless `--dlt` picked one) and opens it live.
+fn open_live(
+    args: &Args,
+) -> Result<(pcap::Capture<pcap::Active>, SourceInfo), Box<dyn std::error::Error>> {
+    // Catch the most common failure here, before prompting or touching the terminal
+    if preflight::can_capture() == Some(false) {
+        #[cfg(target_os = "linux")]
+        preflight::exit_with_hint("missing CAP_NET_RAW (not running as root)");
+        #[cfg(not(target_os = "linux"))]
+        preflight::exit_with_hint("no access to the capture devices");
+    }
+
+    let devices = pcap::Device::list()?;
+    let selected_device = match &args.interface {
+        Some(name) => devices
+            .into_iter()
+            .find(|d| d.name == *name)
+            .ok_or_else(|| format!("No such interface: {} (see --list)", name))?,
+        None => {
+            print_devices(&devices);
+            print!("Select Interface Number: ");
+            io::stdout().flush()?;
+
+            let mut input = String::new();
+            io::stdin().read_line(&mut input)?;
+            let index: usize = input.trim().parse().map_err(|_| "Invalid selection")?;
+            devices.get(index).ok_or("Invalid selection")?.clone()
+        }
+    };
+    let (promisc, immediate) = (true, true);
+    let name = selected_device.name.clone();
+    let addresses = selected_device
+        .addresses
+        .iter()
+        .map(|a| a.addr.to_string())
+        .collect();
+
+    let mut cap = pcap::Capture::from_device(selected_device)?
+        .promisc(promisc)
+        .immediate_mode(immediate)
+        .open()
+        .inspect_err(|e| {
+            if preflight::is_permission_error(e) {
+                preflight::exit_with_hint(&e.to_string());
+            }
+        })?;
+
+    // 1b. Datalink Selection (only matters for interfaces offering more than one)
+    if let Some(name) = &args.dlt {
+        let linktype = pcap::Linktype::from_name(name)
+            .map_err(|_| format!("Unknown datalink type: {}", name))?;
+        cap.set_datalink(linktype)?;
+    } else if args.interface.is_none() {
+        // Scripted starts (--interface) keep the device's default datalink
+        let datalinks = cap.list_datalinks()?;
+        if datalinks.len() > 1 {
+            println!("--- Available Datalinks ---");
+            for (i, dlt) in datalinks.iter().enumerate() {
+                println!(
+                    "[{}] {} ({})",
+                    i,
+                    dlt.get_name().unwrap_or_else(|_| format!("DLT {}", dlt.0)),
+                    dlt.get_description().unwrap_or_default()
+                );
+            }
+            print!("Select Datalink Number [Enter = default]: ");
+            io::stdout().flush()?;
+
+            let mut input = String::new();
+            io::stdin().read_line(&mut input)?;
+            if !input.trim().is_empty() {
+                let index: usize = input.trim().parse().map_err(|_| "Invalid selection")?;
+                let linktype = *datalinks.get(index).ok_or("Invalid selection")?;
+                cap.set_datalink(linktype)?;
+            }
+        }
+    }
+
+    let info = SourceInfo {
+        name,
+        linktype: cap.get_datalink(),
+        addresses,
+        modes: Some((promisc, immediate)),
+        filter: None,
+    };
+    Ok((cap, info))
+}
+
+/// Opens `name` again after it went away, with the modes and datalink of the original
+/// capture so the parser keeps working unchanged.
+fn reopen_device(
+    name: &str,
+    (promisc, immediate): (bool, bool),
+    linktype: pcap::Linktype,
+    filter: Option<&str>,
+) -> Result<pcap::Capture<pcap::Active>, pcap::Error> {
+    let mut cap = pcap::Capture::from_device(name)?
+        .promisc(promisc)
+        .immediate_mode(immediate)
+        .open()?;
+    if cap.get_datalink() != linktype {
+        cap.set_datalink(linktype)?;
+    }
+    if let Some(expr) = filter {
+        cap.filter(expr, true)?;
+    }
+    Ok(cap)
+}
+
+fn toggle_chip(enabled_chips: &mut HashSet<&str>, index: usize) {
+    let chip = ui::CHIPS[index];
+    if !enabled_chips.remove(chip) {
+        enabled_chips.insert(chip);
+    }
+}
+
+/// `--read <file>` replays a capture file; `--read -` takes a pcap stream on stdin
+/// (e.g. `ssh host tcpdump -w - | net-sniff --read -`) and runs until it closes.
+fn open_read(source: &Path) -> Result<pcap::Capture<pcap::Offline>, Box<dyn std::error::Error>> {
+    if source == Path::new("-") {
+        // SAFETY: fd 0 stays open for the life of the process, and with --read there
+        // are no interactive prompts reading it; the TUI takes keys from the tty.
+        Ok(unsafe { pcap::Capture::from_raw_fd(0) }?)
+    } else {
+        Ok(pcap::Capture::from_file(source)?)
+    }
+}
+
+/// Prints one line per packet, tcpdump style, until `deadline` or the capture ends,
+/// then the same capture summary as the TUI on stderr.
+fn run_headless(
+    rx: &mpsc::Receiver<PacketData>,
+    deadline: Option<Instant>,
+    started: Instant,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut out = io::stdout().lock();
+    let mut total_packets: u64 = 0;
+    let mut total_bytes: u64 = 0;
+    while deadline.is_none_or(|d| Instant::now() < d) {
+        let packet = match rx.recv_timeout(Duration::from_millis(100)) {
+            Ok(packet) => packet,
+            Err(mpsc::RecvTimeoutError::Timeout) => continue,
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        };
+        if packet.duplicate {
+            continue;
+        }
+        total_packets += 1;
+        total_bytes += packet.length as u64;
+        // A closed pipe (e.g. `| head`) just ends the run
+        if writeln!(
+            out,
+            "{} {:<12} {}",
+            packet.time_label, packet.app_name, packet.summary
+        )
+        .is_err()
+        {
+            break;
+        }
+    }
+    // stderr, so the summary doesn't end up among the packet lines
+    eprintln!(
+        "Captured {} packets ({} bytes) in {}s",
+        total_packets,
+        total_bytes,
+        started.elapsed().as_secs()
+    );
     Ok(())
 }
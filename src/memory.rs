@@ -0,0 +1,130 @@
+//! `--max-mem`: rough accounting of the buffers the UI thread keeps, and eviction
+//! once they approach the budget.
+
+use crate::capture::PacketData;
+use crate::{ConnectionKey, ConnectionStats, FlowKey};
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::mem::size_of;
+use std::time::Instant;
+
+/// Eviction starts above this share of the budget...
+const HIGH_WATER_PERCENT: usize = 90;
+/// ...and stops once usage is back under this share.
+const LOW_WATER_PERCENT: usize = 75;
+
+/// Heap and inline bytes held by one feed entry.
+pub fn packet_size(packet: &PacketData) -> usize {
+    size_of::<PacketData>()
+        + packet.summary.len()
+        + packet.time_label.len()
+        + packet.full_details.len()
+        + packet.hex_dump.len()
+        + packet.app_name.len()
+        + packet.source.len()
+        + packet.dest.len()
+        + packet.proto_label.len()
+        + packet.raw.as_ref().map_or(0, Vec::len)
+        + packet.text.as_ref().map_or(0, String::len)
+}
+
+fn connection_size(key: &ConnectionKey) -> usize {
+    size_of::<(ConnectionKey, ConnectionStats)>()
+        + key.0.len()
+        + key.1.len()
+        + key.2.len()
+        + key.3.len()
+}
+
+fn flow_size(flow: &FlowKey) -> usize {
+    size_of::<(FlowKey, ConnectionStats)>() - size_of::<(ConnectionKey, ConnectionStats)>()
+        + connection_size(&flow.0)
+}
+
+/// Approximate bytes used by the feed, the packets held back by a pause, the
+/// connections table and the per-flow totals behind it.
+pub fn usage(
+    packets: &VecDeque<PacketData>,
+    backlog: &VecDeque<PacketData>,
+    connections: &HashMap<ConnectionKey, ConnectionStats>,
+    flows: &HashMap<FlowKey, ConnectionStats>,
+) -> usize {
+    packets
+        .iter()
+        .chain(backlog)
+        .map(packet_size)
+        .sum::<usize>()
+        + connections.keys().map(connection_size).sum::<usize>()
+        + flows.keys().map(flow_size).sum::<usize>()
+}
+
+/// Whether the pause backlog may take another packet. It stops short of the
+/// high-water mark, so holding packets back never evicts the feed being looked at.
+pub fn backlog_has_room(budget: usize, used: usize) -> bool {
+    used < budget / 100 * HIGH_WATER_PERCENT
+}
+
+/// Brings usage back under the low-water mark once it passes the high-water mark.
+/// Connections idle the longest go first, since they're the least likely to be
+/// looked at; the oldest feed packets follow if that isn't enough. The pause backlog
+/// counts towards usage but is never evicted. Returns the usage afterwards.
+pub fn enforce_budget(
+    budget: usize,
+    packets: &mut VecDeque<PacketData>,
+    backlog: &VecDeque<PacketData>,
+    retained_bytes: &mut usize,
+    connections: &mut HashMap<ConnectionKey, ConnectionStats>,
+    flows: &mut HashMap<FlowKey, ConnectionStats>,
+) -> usize {
+    let mut used = usage(packets, backlog, connections, flows);
+    if used <= budget / 100 * HIGH_WATER_PERCENT {
+        return used;
+    }
+    let target = budget / 100 * LOW_WATER_PERCENT;
+
+    // Flows and rows are roughly paired, so each takes half of the excess
+    let excess = used.saturating_sub(target);
+    let freed_flows = evict_idle(flows, excess / 2, flow_size);
+    let freed_connections = evict_idle(connections, excess - excess / 2, connection_size);
+    used -= freed_flows + freed_connections;
+
+    let mut evicted_packets = 0;
+    while used > target && evicted_packets < packets.len() {
+        used -= packet_size(&packets[evicted_packets]);
+        evicted_packets += 1;
+    }
+    for packet in packets.drain(..evicted_packets) {
+        *retained_bytes -= packet.raw.map_or(0, |raw| raw.len());
+    }
+
+    log::info!(
+        "memory budget: evicted {} KiB of connection state and {} packets, now ~{} bytes",
+        (freed_flows + freed_connections) / 1024,
+        evicted_packets,
+        used
+    );
+    used
+}
+
+/// Drops the longest-idle entries until about `goal` bytes are freed; returns the
+/// bytes actually freed.
+fn evict_idle<K: Clone + Eq + Hash>(
+    map: &mut HashMap<K, ConnectionStats>,
+    goal: usize,
+    size: fn(&K) -> usize,
+) -> usize {
+    let mut idle: Vec<(K, Option<Instant>)> = map
+        .iter()
+        .map(|(key, stats)| (key.clone(), stats.last_seen))
+        .collect();
+    idle.sort_by_key(|(_, last_seen)| *last_seen);
+    let mut freed = 0;
+    for (key, _) in idle {
+        if freed >= goal {
+            break;
+        }
+        freed += size(&key);
+        map.remove(&key);
+    }
+    freed
+}
@@ -0,0 +1,31 @@
+//! Background chatter kept out of the feed by default.
+//!
+//! Discovery protocols multicast announcements every few seconds whether anyone is
+//! listening or not. They're dropped in the parser thread unless `--show-multicast`
+//! is given or `M` turns them back on; add rules to [`RULES`] for other noisy
+//! protocols.
+
+use crate::capture::PacketData;
+
+/// How a rule recognizes a noisy packet.
+enum Rule {
+    /// Decoded protocol label
+    Label(&'static str),
+    /// Destination address, with or without a port
+    Dest(&'static str),
+}
+
+const RULES: &[Rule] = &[
+    // SSDP (UPnP discovery), by label and by its IPv4 and IPv6 site-local groups
+    Rule::Label("SSDP"),
+    Rule::Dest("239.255.255.250"),
+    Rule::Dest("ff05::c"),
+];
+
+/// True when `packet` matches one of the [`RULES`].
+pub fn is_noise(packet: &PacketData) -> bool {
+    RULES.iter().any(|rule| match rule {
+        Rule::Label(label) => packet.proto_label == *label,
+        Rule::Dest(addr) => packet.dest.contains(addr),
+    })
+}
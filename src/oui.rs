@@ -0,0 +1,122 @@
+//! MAC vendor lookup by OUI (the first three bytes of the address).
+//!
+//! A handful of common vendors are built in; `--oui-file` loads a full IEEE
+//! `oui.txt` or Wireshark `manuf` file on top of them.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+const BUILTIN: &[([u8; 3], &str)] = &[
+    ([0x00, 0x00, 0x0C], "Cisco Systems"),
+    ([0x00, 0x18, 0x0A], "Cisco Meraki"),
+    ([0x00, 0x05, 0x85], "Juniper Networks"),
+    ([0x00, 0x1C, 0x73], "Arista Networks"),
+    ([0x00, 0x1B, 0x17], "Palo Alto Networks"),
+    ([0x00, 0x09, 0x0F], "Fortinet"),
+    ([0x24, 0xA4, 0x3C], "Ubiquiti"),
+    ([0x04, 0x18, 0xD6], "Ubiquiti"),
+    ([0x00, 0x0D, 0xB9], "PC Engines"),
+    ([0x00, 0x11, 0x32], "Synology"),
+    ([0x00, 0x03, 0x93], "Apple"),
+    ([0x00, 0x1B, 0x63], "Apple"),
+    ([0x00, 0x1A, 0x11], "Google"),
+    ([0x3C, 0x5A, 0xB4], "Google"),
+    ([0x00, 0x1B, 0x21], "Intel"),
+    ([0x00, 0xE0, 0x4C], "Realtek"),
+    ([0x00, 0x04, 0x4B], "NVIDIA"),
+    ([0x00, 0x0E, 0xC6], "ASIX Electronics"),
+    ([0x00, 0x14, 0x22], "Dell"),
+    ([0x00, 0x25, 0x90], "Super Micro Computer"),
+    ([0x00, 0x17, 0x88], "Philips Lighting"),
+    ([0x00, 0x0B, 0x82], "Grandstream Networks"),
+    ([0x00, 0x04, 0xF2], "Polycom"),
+    ([0xB8, 0x27, 0xEB], "Raspberry Pi Foundation"),
+    ([0xDC, 0xA6, 0x32], "Raspberry Pi Trading"),
+    ([0xE4, 0x5F, 0x01], "Raspberry Pi Trading"),
+    ([0x00, 0x50, 0x56], "VMware"),
+    ([0x00, 0x0C, 0x29], "VMware"),
+    ([0x00, 0x05, 0x69], "VMware"),
+    ([0x08, 0x00, 0x27], "VirtualBox"),
+    ([0x00, 0x15, 0x5D], "Microsoft Hyper-V"),
+    ([0x00, 0x0D, 0x3A], "Microsoft"),
+    ([0x00, 0x1C, 0x42], "Parallels"),
+    ([0x00, 0x16, 0x3E], "Xensource"),
+];
+
+static VENDORS: OnceLock<HashMap<[u8; 3], String>> = OnceLock::new();
+
+/// Loads the built-in table plus, if given, every entry of `path`. Without a call
+/// the first lookup falls back to the built-in table alone.
+pub fn init(path: Option<&Path>) -> Result<(), String> {
+    let mut vendors = builtin();
+    if let Some(path) = path {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read OUI file {}: {}", path.display(), e))?;
+        let before = vendors.len();
+        vendors.extend(text.lines().filter_map(parse_line));
+        log::info!(
+            "loaded {} OUI entries from {}",
+            vendors.len() - before,
+            path.display()
+        );
+    }
+    let _ = VENDORS.set(vendors);
+    Ok(())
+}
+
+fn builtin() -> HashMap<[u8; 3], String> {
+    BUILTIN
+        .iter()
+        .map(|(oui, name)| (*oui, name.to_string()))
+        .collect()
+}
+
+/// One `00-00-0C   (hex)\t\tCisco Systems, Inc` (IEEE) or `00:00:0C\tCisco\tCisco
+/// Systems, Inc` (Wireshark) line. Wireshark's longer `/28` and `/36` prefixes are
+/// skipped.
+fn parse_line(line: &str) -> Option<([u8; 3], String)> {
+    let line = line.trim();
+    if line.starts_with('#') {
+        return None;
+    }
+    let (prefix, rest) = line.split_once(char::is_whitespace)?;
+    let hex: Vec<u8> = prefix
+        .split([':', '-'])
+        .map(|byte| u8::from_str_radix(byte, 16))
+        .collect::<Result<_, _>>()
+        .ok()?;
+    let oui: [u8; 3] = hex.try_into().ok()?;
+
+    let rest = rest.trim_start();
+    let rest = rest.strip_prefix("(hex)").unwrap_or(rest);
+    // Wireshark puts the short name first and the full one after a tab
+    let name = rest.rsplit('\t').next()?.trim();
+    (!name.is_empty()).then(|| (oui, name.to_string()))
+}
+
+/// Vendor note for a MAC, e.g. "Cisco Systems". Multicast, broadcast and locally
+/// administered addresses aren't vendor-assigned, so they're described instead.
+pub fn describe(mac: &[u8]) -> Option<String> {
+    let oui: [u8; 3] = mac.get(..3)?.try_into().ok()?;
+    if mac.iter().all(|&b| b == 0xFF) {
+        return Some("broadcast".into());
+    }
+    if oui[0] & 0x01 != 0 {
+        return Some("multicast".into());
+    }
+    if oui[0] & 0x02 != 0 {
+        return Some("locally administered".into());
+    }
+    VENDORS.get_or_init(builtin).get(&oui).cloned()
+}
+
+/// `aa:bb:cc:dd:ee:ff (Vendor)`, or just the address when the vendor is unknown.
+pub fn format_with_vendor(mac: &[u8]) -> String {
+    let address = crate::capture::format_mac(mac);
+    match describe(mac) {
+        Some(vendor) => format!("{} ({})", address, vendor),
+        None => address,
+    }
+}
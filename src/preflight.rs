@@ -0,0 +1,75 @@
+//! Startup check that live capture is permitted, so a missing privilege is reported
+//! with the fix before the TUI takes over the terminal.
+
+use std::env;
+use std::process;
+
+/// CAP_NET_RAW, needed for AF_PACKET sockets.
+#[cfg(target_os = "linux")]
+const CAP_NET_RAW: u32 = 13;
+
+/// Whether this process may open live captures. `None` when the platform offers no
+/// cheap way to tell; the open attempt itself then decides.
+pub fn can_capture() -> Option<bool> {
+    #[cfg(target_os = "linux")]
+    {
+        // Effective capabilities cover root too, and any that were dropped
+        let status = std::fs::read_to_string("/proc/self/status").ok()?;
+        let cap_eff = status
+            .lines()
+            .find_map(|line| line.strip_prefix("CapEff:"))?
+            .trim();
+        let caps = u64::from_str_radix(cap_eff, 16).ok()?;
+        Some(caps & (1 << CAP_NET_RAW) != 0)
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        // Capturing means opening a BPF device; busy is fine, denied is not
+        match std::fs::File::open("/dev/bpf0") {
+            Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => Some(false),
+            _ => Some(true),
+        }
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    None
+}
+
+/// True for pcap open errors that come down to missing privileges.
+pub fn is_permission_error(error: &pcap::Error) -> bool {
+    let text = error.to_string().to_lowercase();
+    text.contains("permission") || text.contains("not permitted")
+}
+
+/// Prints how to grant capture rights, with this binary's real path, and exits.
+pub fn exit_with_hint(reason: &str) -> ! {
+    let exe = env::current_exe()
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|_| "net-sniff".into());
+    let args: Vec<String> = env::args().skip(1).collect();
+    let rerun = if args.is_empty() {
+        exe.clone()
+    } else {
+        format!("{} {}", exe, args.join(" "))
+    };
+
+    eprintln!("net-sniff cannot capture packets: {}", reason);
+    eprintln!();
+    eprintln!("Run it with root privileges:");
+    eprintln!("    sudo {}", rerun);
+    #[cfg(target_os = "linux")]
+    {
+        eprintln!();
+        eprintln!("or grant the capture capabilities to the binary once:");
+        eprintln!("    sudo setcap cap_net_raw,cap_net_admin=eip {}", exe);
+    }
+    #[cfg(target_os = "macos")]
+    {
+        eprintln!();
+        eprintln!("or give your user read access to /dev/bpf* (e.g. Wireshark's ChmodBPF).");
+    }
+    eprintln!();
+    eprintln!("Capture files can be analyzed without privileges: --read <file>");
+    process::exit(1);
+}
@@ -5,27 +5,46 @@ use procfs;
 
 // In sysinfo 0.30+, traits like ProcessExt/SystemExt are gone.
 // We just need the main types.
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+use sysinfo::{Pid, System};
 #[cfg(target_os = "macos")]
-use sysinfo::System;
+use std::process::Command;
+
+/// Consecutive failed refreshes before process resolution is given up on.
+#[cfg(target_os = "linux")]
+const MAX_REFRESH_FAILURES: u32 = 3;
 
 pub struct ProcessResolver {
     #[allow(dead_code)]
     inode_to_name: HashMap<u64, String>,
-    #[cfg(target_os = "macos")]
+    /// Why process attribution can't work here (e.g. restricted /proc); once set,
+    /// refreshes and lookups stop touching procfs
+    unavailable: Option<String>,
+    #[allow(dead_code)]
+    failures: u32,
+    #[cfg(any(target_os = "macos", target_os = "windows"))]
     sys: System,
+    /// Local port -> process name, rebuilt from `lsof` (macOS) or the IP Helper
+    /// owner tables (Windows) on every refresh
+    #[cfg(any(target_os = "macos", target_os = "windows"))]
+    port_to_name: HashMap<u16, String>,
 }
 
 impl ProcessResolver {
     pub fn new() -> Self {
-        #[cfg(target_os = "macos")]
+        #[cfg(any(target_os = "macos", target_os = "windows"))]
         let mut sys = System::new_all();
-        #[cfg(target_os = "macos")]
+        #[cfg(any(target_os = "macos", target_os = "windows"))]
         sys.refresh_all();
 
         let mut resolver = Self {
             inode_to_name: HashMap::new(),
-            #[cfg(target_os = "macos")]
+            unavailable: None,
+            failures: 0,
+            #[cfg(any(target_os = "macos", target_os = "windows"))]
             sys,
+            #[cfg(any(target_os = "macos", target_os = "windows"))]
+            port_to_name: HashMap::new(),
         };
         resolver.refresh();
         resolver
@@ -36,58 +55,244 @@ impl ProcessResolver {
 
         #[cfg(target_os = "linux")]
         {
-            if let Ok(all_proc) = procfs::process::all_processes() {
-                for p in all_proc.flatten() {
-                    if let (Ok(stat), Ok(fds)) = (p.stat(), p.fd()) {
-                        let name = stat.comm;
-                        for fd in fds.flatten() {
-                            if let procfs::process::FDTarget::Socket(inode) = fd.target {
-                                self.inode_to_name.insert(inode, name.clone());
+            if self.unavailable.is_some() {
+                return;
+            }
+            // Inside some containers /proc is missing or other processes' fds are
+            // unreadable; the map then stays empty and every packet is "Unknown"
+            let mut problem = None;
+            let mut readable_fd_dirs = 0;
+            match procfs::process::all_processes() {
+                Ok(all_proc) => {
+                    for p in all_proc.flatten() {
+                        match (p.stat(), p.fd()) {
+                            (Ok(stat), Ok(fds)) => {
+                                readable_fd_dirs += 1;
+                                let name = stat.comm;
+                                for fd in fds.flatten() {
+                                    if let procfs::process::FDTarget::Socket(inode) = fd.target {
+                                        self.inode_to_name.insert(inode, name.clone());
+                                    }
+                                }
                             }
+                            (_, Err(e)) | (Err(e), _) => problem = Some(describe_proc_error(&e)),
                         }
                     }
                 }
+                Err(e) => problem = Some(describe_proc_error(&e)),
+            }
+            if let Err(e) = procfs::net::tcp() {
+                problem = Some(describe_proc_error(&e));
+                readable_fd_dirs = 0;
+            }
+
+            // Our own fds are always readable, so one readable process proves nothing
+            match problem {
+                Some(reason) if readable_fd_dirs <= 1 => {
+                    self.failures += 1;
+                    if self.failures >= MAX_REFRESH_FAILURES {
+                        log::warn!("process resolution disabled: {}", reason);
+                        self.unavailable = Some(reason);
+                        self.inode_to_name.clear();
+                    }
+                }
+                _ => self.failures = 0,
             }
         }
 
         #[cfg(target_os = "macos")]
         {
+            if self.unavailable.is_some() {
+                return;
+            }
             // Direct method call, no trait import needed in 0.30+
             self.sys.refresh_processes();
+            self.port_to_name.clear();
+            match lsof_sockets() {
+                Ok(sockets) => {
+                    for (port, pid, command) in sockets {
+                        // lsof cuts command names at 9 characters; sysinfo has the full one
+                        let name = self
+                            .sys
+                            .process(Pid::from_u32(pid))
+                            .map_or(command, |p| p.name().to_string());
+                        self.port_to_name.insert(port, name);
+                    }
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    log::warn!("process resolution disabled: lsof not found");
+                    self.unavailable = Some("lsof not available".into());
+                }
+                Err(e) => log::debug!("lsof failed: {}", e),
+            }
+        }
+
+        #[cfg(target_os = "windows")]
+        {
+            self.sys.refresh_processes();
+            self.port_to_name.clear();
+            for (port, pid) in windows_sockets() {
+                // Protected processes (and PIDs 0/4) can't be opened for their name
+                let name = self
+                    .sys
+                    .process(Pid::from_u32(pid))
+                    .map(|p| p.name().to_string())
+                    .filter(|name| !name.is_empty())
+                    .unwrap_or_else(|| "System".into());
+                self.port_to_name.insert(port, name);
+            }
         }
     }
 
+    /// Set once procfs has failed repeatedly, with a short reason for the footer.
+    pub fn unavailable(&self) -> Option<&str> {
+        self.unavailable.as_deref()
+    }
+
 pub fn resolve_port(&self, _local_port: u16) -> String {
     #[cfg(target_os = "linux")]
+    if self.unavailable.is_none()
     {
-        // Check TCP Table
-        if let Ok(tcp) = procfs::net::tcp() {
-            if let Some(entry) = tcp.iter().find(|e| e.local_address.port() == _local_port) {
-                // Use .get().cloned() to return early ONLY if found
-                if let Some(name) = self.inode_to_name.get(&entry.inode) {
-                    return name.clone();
-                }
-            }
-        }
-        
-        // Check UDP Table
-        if let Ok(udp) = procfs::net::udp() {
-            if let Some(entry) = udp.iter().find(|e| e.local_address.port() == _local_port) {
-                if let Some(name) = self.inode_to_name.get(&entry.inode) {
-                    return name.clone();
-                }
+        // TCP before UDP; the *6 tables hold the IPv6 and dual-stack sockets
+        let tcp = [procfs::net::tcp(), procfs::net::tcp6()]
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|e| e.local_address.port() == _local_port)
+            .map(|e| e.inode);
+        let udp = [procfs::net::udp(), procfs::net::udp6()]
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|e| e.local_address.port() == _local_port)
+            .map(|e| e.inode);
+        for inode in tcp.chain(udp) {
+            if let Some(name) = self.inode_to_name.get(&inode) {
+                return name.clone();
             }
         }
         // IMPORTANT: We need a fallback within the Linux block 
         // if the port was found in tables but not in our inode map
     }
 
-    #[cfg(target_os = "macos")]
-    {
-        return "macOS-App".to_string();
+    #[cfg(any(target_os = "macos", target_os = "windows"))]
+    if let Some(name) = self.port_to_name.get(&_local_port) {
+        return name.clone();
     }
 
     // Final fallback for all platforms
     "Unknown".to_string()
 }
 }
+
+/// (local port, PID, command) for every TCP and UDP socket, from `lsof`'s
+/// machine-readable output: a `p<pid>` and `c<command>` line per process, then an
+/// `n<address>` line per socket, e.g. `n10.0.0.2:52345->17.253.1.1:443`.
+#[cfg(target_os = "macos")]
+fn lsof_sockets() -> std::io::Result<Vec<(u16, u32, String)>> {
+    // Exits non-zero when nothing matched, so only the output matters
+    let output = Command::new("lsof")
+        .args(["-nP", "-iTCP", "-iUDP", "-F", "pcn"])
+        .output()?;
+    let mut sockets = Vec::new();
+    let mut pid = 0;
+    let mut command = String::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let mut chars = line.chars();
+        match chars.next() {
+            Some('p') => pid = chars.as_str().parse().unwrap_or(0),
+            Some('c') => command = chars.as_str().to_string(),
+            Some('n') => {
+                let local = chars.as_str().split("->").next().unwrap_or_default();
+                if let Some(port) = local
+                    .rsplit_once(':')
+                    .and_then(|(_, port)| port.parse().ok())
+                {
+                    sockets.push((port, pid, command.clone()));
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(sockets)
+}
+
+/// (local port, owning PID) for every TCP and UDP socket, IPv4 and IPv6. UDP goes
+/// first so a TCP socket on the same port wins, like the procfs lookup on Linux.
+#[cfg(target_os = "windows")]
+fn windows_sockets() -> Vec<(u16, u32)> {
+    use windows::Win32::NetworkManagement::IpHelper::{
+        GetExtendedTcpTable, GetExtendedUdpTable, MIB_TCP6ROW_OWNER_PID, MIB_TCPROW_OWNER_PID,
+        MIB_UDP6ROW_OWNER_PID, MIB_UDPROW_OWNER_PID, TCP_TABLE_OWNER_PID_ALL, UDP_TABLE_OWNER_PID,
+    };
+    use windows::Win32::Foundation::FALSE;
+    use windows::Win32::Networking::WinSock::{AF_INET, AF_INET6};
+
+    // Ports are stored in network byte order in the low 16 bits
+    let port = |raw: u32| u16::from_be(raw as u16);
+    let mut sockets = Vec::new();
+    for family in [AF_INET.0 as u32, AF_INET6.0 as u32] {
+        let udp = owner_table(|buf, size| unsafe {
+            GetExtendedUdpTable(Some(buf), size, FALSE, family, UDP_TABLE_OWNER_PID, 0)
+        });
+        let tcp = owner_table(|buf, size| unsafe {
+            GetExtendedTcpTable(Some(buf), size, FALSE, family, TCP_TABLE_OWNER_PID_ALL, 0)
+        });
+        if family == AF_INET.0 as u32 {
+            let udp = table_rows::<MIB_UDPROW_OWNER_PID>(&udp);
+            sockets.extend(udp.iter().map(|r| (port(r.dwLocalPort), r.dwOwningPid)));
+            let tcp = table_rows::<MIB_TCPROW_OWNER_PID>(&tcp);
+            sockets.extend(tcp.iter().map(|r| (port(r.dwLocalPort), r.dwOwningPid)));
+        } else {
+            let udp = table_rows::<MIB_UDP6ROW_OWNER_PID>(&udp);
+            sockets.extend(udp.iter().map(|r| (port(r.dwLocalPort), r.dwOwningPid)));
+            let tcp = table_rows::<MIB_TCP6ROW_OWNER_PID>(&tcp);
+            sockets.extend(tcp.iter().map(|r| (port(r.dwLocalPort), r.dwOwningPid)));
+        }
+    }
+    sockets
+}
+
+/// Runs one of the `GetExtended*Table` calls, growing the buffer to the size it asks
+/// for. An empty buffer means the table couldn't be read.
+#[cfg(target_os = "windows")]
+fn owner_table(fetch: impl Fn(*mut std::ffi::c_void, *mut u32) -> u32) -> Vec<u32> {
+    const NO_ERROR: u32 = 0;
+    const ERROR_INSUFFICIENT_BUFFER: u32 = 122;
+    // u32 words keep the rows aligned; the table can grow between the two calls
+    let mut buf: Vec<u32> = Vec::new();
+    loop {
+        let mut size = (buf.len() * 4) as u32;
+        match fetch(buf.as_mut_ptr().cast(), &mut size) {
+            NO_ERROR => return buf,
+            ERROR_INSUFFICIENT_BUFFER => buf.resize((size as usize).div_ceil(4), 0),
+            status => {
+                log::debug!("socket owner table unavailable (error {})", status);
+                return Vec::new();
+            }
+        }
+    }
+}
+
+/// The rows of an owner table: a `dwNumEntries` count followed by that many `T`.
+#[cfg(target_os = "windows")]
+fn table_rows<T: Copy>(table: &[u32]) -> Vec<T> {
+    let Some((&count, rows)) = table.split_first() else {
+        return Vec::new();
+    };
+    let count = (count as usize).min(std::mem::size_of_val(rows) / std::mem::size_of::<T>());
+    let first = rows.as_ptr().cast::<T>();
+    // SAFETY: `count` rows fit in `rows`, which is u32-aligned like every row type
+    (0..count)
+        .map(|i| unsafe { first.add(i).read_unaligned() })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn describe_proc_error(error: &procfs::ProcError) -> String {
+    match error {
+        procfs::ProcError::PermissionDenied(_) => "restricted /proc".into(),
+        procfs::ProcError::NotFound(_) => "/proc not available".into(),
+        other => format!("/proc unreadable: {}", other),
+    }
+}
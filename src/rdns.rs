@@ -0,0 +1,147 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::net::IpAddr;
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+
+/// Resolved names kept before the oldest are forgotten.
+const CACHE_CAPACITY: usize = 4096;
+/// Addresses waiting for the lookup thread; more are dropped and asked for again later.
+const QUEUE_CAPACITY: usize = 256;
+
+/// Reverse-DNS names for the feed. PTR lookups can take seconds, so they run on a
+/// background thread and the UI only ever reads whatever has been resolved so far.
+pub struct ReverseDns {
+    names: Arc<Mutex<HashMap<IpAddr, String>>>,
+    queue: SyncSender<IpAddr>,
+    /// Addresses already handed to the lookup thread, including ones without a name
+    requested: HashSet<IpAddr>,
+}
+
+impl ReverseDns {
+    pub fn start() -> Self {
+        let names = Arc::new(Mutex::new(HashMap::new()));
+        let (queue, pending) = mpsc::sync_channel(QUEUE_CAPACITY);
+        let worker_names = Arc::clone(&names);
+        thread::spawn(move || resolve_loop(pending, worker_names));
+        Self {
+            names,
+            queue,
+            requested: HashSet::new(),
+        }
+    }
+
+    /// Queues `addr` for a lookup unless it was asked for before. Text that isn't an
+    /// IP address (MACs, "unknown") and addresses no PTR record describes are skipped.
+    pub fn request(&mut self, addr: &str) {
+        let Ok(ip) = addr.parse::<IpAddr>() else {
+            return;
+        };
+        if !is_resolvable(ip) || self.requested.contains(&ip) {
+            return;
+        }
+        if self.requested.len() >= CACHE_CAPACITY {
+            // Names already found stay cached; failures just get one more try
+            self.requested.clear();
+        }
+        if self.queue.try_send(ip).is_ok() {
+            self.requested.insert(ip);
+        }
+    }
+
+    pub fn names(&self) -> Option<MutexGuard<'_, HashMap<IpAddr, String>>> {
+        self.names.lock().ok()
+    }
+}
+
+fn resolve_loop(pending: Receiver<IpAddr>, names: Arc<Mutex<HashMap<IpAddr, String>>>) {
+    // Insertion order, so the cache drops its oldest entry once full
+    let mut order = VecDeque::new();
+    for ip in pending {
+        let Some(name) = lookup(ip) else {
+            continue;
+        };
+        let Ok(mut names) = names.lock() else {
+            return;
+        };
+        if names.insert(ip, name).is_none() {
+            order.push_back(ip);
+        }
+        while order.len() > CACHE_CAPACITY {
+            if let Some(oldest) = order.pop_front() {
+                names.remove(&oldest);
+            }
+        }
+    }
+}
+
+fn is_resolvable(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            !(v4.is_broadcast() || v4.is_multicast() || v4.is_unspecified() || v4.is_loopback())
+        }
+        IpAddr::V6(v6) => !(v6.is_multicast() || v6.is_unspecified() || v6.is_loopback()),
+    }
+}
+
+/// The PTR name for `ip` through the system resolver, so /etc/hosts and any local DNS
+/// setup apply just as they do for other programs.
+#[cfg(unix)]
+fn lookup(ip: IpAddr) -> Option<String> {
+    use std::ffi::CStr;
+    use std::mem::{size_of, zeroed};
+
+    // NI_MAXHOST
+    let mut host = [0 as libc::c_char; 1025];
+    let mut resolve = |addr: *const libc::sockaddr, len: usize| unsafe {
+        libc::getnameinfo(
+            addr,
+            len as libc::socklen_t,
+            host.as_mut_ptr(),
+            host.len() as libc::socklen_t,
+            std::ptr::null_mut(),
+            0,
+            // Fail rather than hand back the numeric form
+            libc::NI_NAMEREQD,
+        )
+    };
+    let status = match ip {
+        IpAddr::V4(v4) => {
+            let mut addr: libc::sockaddr_in = unsafe { zeroed() };
+            #[cfg(target_os = "macos")]
+            {
+                addr.sin_len = size_of::<libc::sockaddr_in>() as u8;
+            }
+            addr.sin_family = libc::AF_INET as libc::sa_family_t;
+            addr.sin_addr.s_addr = u32::from_ne_bytes(v4.octets());
+            resolve(
+                &addr as *const libc::sockaddr_in as *const libc::sockaddr,
+                size_of::<libc::sockaddr_in>(),
+            )
+        }
+        IpAddr::V6(v6) => {
+            let mut addr: libc::sockaddr_in6 = unsafe { zeroed() };
+            #[cfg(target_os = "macos")]
+            {
+                addr.sin6_len = size_of::<libc::sockaddr_in6>() as u8;
+            }
+            addr.sin6_family = libc::AF_INET6 as libc::sa_family_t;
+            addr.sin6_addr.s6_addr = v6.octets();
+            resolve(
+                &addr as *const libc::sockaddr_in6 as *const libc::sockaddr,
+                size_of::<libc::sockaddr_in6>(),
+            )
+        }
+    };
+    if status != 0 {
+        return None;
+    }
+    let name = unsafe { CStr::from_ptr(host.as_ptr()) };
+    Some(name.to_string_lossy().into_owned())
+}
+
+/// No resolver binding on this platform yet; addresses are shown as they are.
+#[cfg(not(unix))]
+fn lookup(_ip: IpAddr) -> Option<String> {
+    None
+}
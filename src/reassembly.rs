@@ -0,0 +1,67 @@
+use etherparse::defrag::{IpDefragPayloadVec, IpDefragPool};
+use etherparse::{
+    Icmpv4Slice, Icmpv6Slice, IpNumber, SlicedPacket, TcpSlice, TransportSlice, UdpSlice,
+};
+use std::time::{Duration, Instant};
+
+/// Incomplete datagrams are dropped after this long without a new fragment (Linux's
+/// default `ipfrag_time`).
+const FRAGMENT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// What became of a frame handed to [`Reassembler::push`].
+pub enum Fragment {
+    /// Not a fragment; parse the frame as usual
+    Whole,
+    /// A fragment was stored, the datagram isn't complete yet
+    Pending,
+    /// This fragment completed the datagram; holds the reassembled IP payload
+    Complete(IpDefragPayloadVec),
+    /// The fragment contradicts what was already buffered (overlap, bad length, ...)
+    Invalid(String),
+}
+
+/// Collects IPv4/IPv6 fragments per (source, destination, id, protocol) until the
+/// datagram is whole. Lives in the capture thread, next to the process resolver.
+pub struct Reassembler {
+    pool: IpDefragPool<Instant>,
+}
+
+impl Reassembler {
+    pub fn new() -> Self {
+        Self {
+            pool: IpDefragPool::new(),
+        }
+    }
+
+    pub fn push(&mut self, packet: &SlicedPacket) -> Fragment {
+        if !packet.is_ip_payload_fragmented() {
+            return Fragment::Whole;
+        }
+
+        let now = Instant::now();
+        self.pool
+            .retain(|last_seen| now.duration_since(*last_seen) < FRAGMENT_TIMEOUT);
+
+        match self.pool.process_sliced_packet(packet, now, ()) {
+            Ok(Some(payload)) => Fragment::Complete(payload),
+            Ok(None) => Fragment::Pending,
+            Err(e) => Fragment::Invalid(e.to_string()),
+        }
+    }
+}
+
+/// Slices the transport header out of a reassembled IP payload.
+pub fn reassembled_transport(payload: &IpDefragPayloadVec) -> Option<TransportSlice<'_>> {
+    let data = payload.payload.as_slice();
+    match payload.ip_number {
+        IpNumber::TCP => TcpSlice::from_slice(data).ok().map(TransportSlice::Tcp),
+        IpNumber::UDP => UdpSlice::from_slice(data).ok().map(TransportSlice::Udp),
+        IpNumber::ICMP => Icmpv4Slice::from_slice(data)
+            .ok()
+            .map(TransportSlice::Icmpv4),
+        IpNumber::IPV6_ICMP => Icmpv6Slice::from_slice(data)
+            .ok()
+            .map(TransportSlice::Icmpv6),
+        _ => None,
+    }
+}
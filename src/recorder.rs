@@ -0,0 +1,155 @@
+use chrono::Local;
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Size at which `--ring-files` moves on to the next file unless `--rotate-mb` or
+/// `--rotate-minutes` says otherwise.
+const RING_FILE_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Per-record header pcap writes in front of every packet.
+const RECORD_HEADER_BYTES: u64 = 16;
+
+/// When the `w` recording moves on to a new file, and how many files it keeps.
+#[derive(Clone, Copy)]
+pub struct Rotation {
+    /// `--rotate-mb`
+    max_bytes: Option<u64>,
+    /// `--rotate-minutes`
+    max_age: Option<Duration>,
+    /// `--ring-files`
+    ring: Option<usize>,
+}
+
+impl Rotation {
+    pub fn new(max_bytes: Option<u64>, max_age: Option<Duration>, ring: Option<usize>) -> Self {
+        // A ring without a limit of its own keeps rotating at the old fixed size
+        let max_bytes = match (max_bytes, max_age, ring) {
+            (None, None, Some(_)) => Some(RING_FILE_BYTES),
+            _ => max_bytes,
+        };
+        Self {
+            max_bytes,
+            max_age,
+            ring,
+        }
+    }
+
+    fn rotates(&self) -> bool {
+        self.max_bytes.is_some() || self.max_age.is_some()
+    }
+
+    fn is_due(&self, written: u64, age: Duration) -> bool {
+        self.max_bytes.is_some_and(|max| written >= max)
+            || self.max_age.is_some_and(|max| age >= max)
+    }
+}
+
+/// The `w` savefile. Normally one growing file for the whole recording; with
+/// `--rotate-mb`/`--rotate-minutes` it moves on to a new numbered file whenever the
+/// current one reaches the limit, and `--ring-files N` additionally deletes the
+/// oldest file so at most N stay on disk, like `tcpdump -C -W`.
+pub struct Recorder {
+    linktype: pcap::Linktype,
+    file: pcap::Savefile,
+    /// `net-sniff_<timestamp>`, shared by every file of this recording
+    stem: String,
+    written: u64,
+    /// When the current file was opened, for `--rotate-minutes`
+    opened_at: Instant,
+    rotation: Rotation,
+    /// Files of this recording still on disk, oldest first
+    files: VecDeque<PathBuf>,
+    /// Files opened so far, counting the current one
+    opened: usize,
+}
+
+impl Recorder {
+    pub fn start(linktype: pcap::Linktype, rotation: Rotation) -> Result<Self, pcap::Error> {
+        let stem = format!("net-sniff_{}", Local::now().format("%Y-%m-%d_%H-%M-%S"));
+        let path = file_path(&stem, rotation.rotates().then_some(1));
+        let file = open(linktype, &path)?;
+        log::info!("saving to {}", path.display());
+        Ok(Self {
+            linktype,
+            file,
+            stem,
+            written: 0,
+            opened_at: Instant::now(),
+            rotation,
+            files: VecDeque::from([path]),
+            opened: 1,
+        })
+    }
+
+    pub fn write(&mut self, packet: &pcap::Packet) {
+        if self.rotation.is_due(self.written, self.opened_at.elapsed()) {
+            self.rotate();
+        }
+        self.file.write(packet);
+        self.written += RECORD_HEADER_BYTES + packet.header.caplen as u64;
+    }
+
+    /// Current file's position in the ring (1-based) and the ring size.
+    pub fn ring_position(&self) -> Option<(usize, usize)> {
+        self.rotation
+            .ring
+            .map(|limit| ((self.opened - 1) % limit + 1, limit))
+    }
+
+    pub fn current_path(&self) -> Option<&PathBuf> {
+        self.files.back()
+    }
+
+    pub fn flush(&mut self) -> Result<(), pcap::Error> {
+        self.file.flush()
+    }
+
+    /// Closes the current file, opens the next one and deletes whatever falls out of
+    /// the ring. A failed open keeps writing to the old file rather than losing packets.
+    fn rotate(&mut self) {
+        let path = file_path(&self.stem, Some(self.opened + 1));
+        match open(self.linktype, &path) {
+            Ok(file) => {
+                if let Err(e) = self.file.flush() {
+                    log::warn!("flushing savefile before rotation failed: {}", e);
+                }
+                self.file = file;
+                self.opened += 1;
+                self.written = 0;
+                self.opened_at = Instant::now();
+                self.files.push_back(path);
+            }
+            Err(e) => {
+                log::error!("cannot rotate to {}: {}", path.display(), e);
+                // Try again after another file's worth of packets or time
+                self.written = 0;
+                self.opened_at = Instant::now();
+                return;
+            }
+        }
+        let Some(limit) = self.rotation.ring else {
+            return;
+        };
+        while self.files.len() > limit {
+            if let Some(oldest) = self.files.pop_front()
+                && let Err(e) = fs::remove_file(&oldest)
+            {
+                log::warn!("cannot remove {}: {}", oldest.display(), e);
+            }
+        }
+    }
+}
+
+fn file_path(stem: &str, index: Option<usize>) -> PathBuf {
+    match index {
+        Some(index) => PathBuf::from(format!("{}_{:03}.pcap", stem, index)),
+        None => PathBuf::from(format!("{}.pcap", stem)),
+    }
+}
+
+/// A dead handle with the active datalink keeps the file header correct.
+fn open(linktype: pcap::Linktype, path: &Path) -> Result<pcap::Savefile, pcap::Error> {
+    pcap::Capture::dead(linktype).and_then(|handle| handle.savefile(path))
+}
@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Streams that go quiet for this long are forgotten.
+const STREAM_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Per-SSRC sequence/timing state, so a payload only counts as RTP once a second
+/// packet continues the same stream.
+struct RtpStream {
+    first_seq: u32,
+    /// Extended (wrap-counted) highest sequence number seen
+    max_seq: u32,
+    received: u64,
+    /// RFC 3550 interarrival jitter, in RTP timestamp units
+    jitter: f64,
+    last_transit: Option<f64>,
+    last_seen: Instant,
+    confirmed: bool,
+}
+
+/// What an RTP packet says about its stream so far.
+pub struct RtpInfo {
+    pub label: String,
+    pub details: String,
+}
+
+/// Recognizes RTP by its header shape plus a consistent SSRC and sequence across
+/// packets, since RTP has no fixed port.
+pub struct RtpTracker {
+    streams: HashMap<u32, RtpStream>,
+    /// Reference point for arrival times in the jitter calculation
+    epoch: Instant,
+}
+
+impl RtpTracker {
+    pub fn new() -> Self {
+        Self {
+            streams: HashMap::new(),
+            epoch: Instant::now(),
+        }
+    }
+
+    /// Feeds one UDP payload. Returns `None` until the SSRC has been seen continuing
+    /// its sequence, and for anything that doesn't look like RTP at all.
+    pub fn observe(&mut self, payload: &[u8], now: Instant) -> Option<RtpInfo> {
+        // Version 2, 12-byte fixed header; payload types 72-76 are RTCP
+        if payload.len() < 12 || payload[0] >> 6 != 2 {
+            return None;
+        }
+        let payload_type = payload[1] & 0x7F;
+        if (72..=76).contains(&payload_type) {
+            return None;
+        }
+        let seq = u16::from_be_bytes([payload[2], payload[3]]);
+        let timestamp = u32::from_be_bytes([payload[4], payload[5], payload[6], payload[7]]);
+        let ssrc = u32::from_be_bytes([payload[8], payload[9], payload[10], payload[11]]);
+        let clock_rate = clock_rate(payload_type);
+        let epoch = self.epoch;
+
+        let Some(stream) = self.streams.get_mut(&ssrc) else {
+            self.streams
+                .retain(|_, s| now.duration_since(s.last_seen) < STREAM_TIMEOUT);
+            self.streams.insert(
+                ssrc,
+                RtpStream {
+                    first_seq: seq as u32,
+                    max_seq: seq as u32,
+                    received: 1,
+                    jitter: 0.0,
+                    last_transit: None,
+                    last_seen: now,
+                    confirmed: false,
+                },
+            );
+            return None;
+        };
+
+        // Place the 16-bit sequence next to the extended maximum, allowing for wrap
+        let cycles = stream.max_seq & !0xFFFF;
+        let mut extended = cycles | seq as u32;
+        let max_low = stream.max_seq as u16;
+        if seq < max_low && max_low - seq > 0x8000 {
+            extended += 0x1_0000;
+        } else if seq > max_low && seq - max_low > 0x8000 {
+            extended = extended.saturating_sub(0x1_0000);
+        }
+        let step = extended as i64 - stream.max_seq as i64;
+        if !stream.confirmed && !(1..=100).contains(&step) {
+            // Not continuing the sequence: probably not RTP after all
+            stream.first_seq = extended;
+            stream.max_seq = extended;
+            stream.received = 1;
+            stream.last_seen = now;
+            return None;
+        }
+        stream.confirmed = true;
+        stream.max_seq = stream.max_seq.max(extended);
+        stream.received += 1;
+        stream.last_seen = now;
+
+        if let Some(rate) = clock_rate {
+            // Transit time in timestamp units; only differences between packets matter
+            let arrival = now.duration_since(epoch).as_secs_f64() * rate as f64;
+            let transit = arrival - timestamp as f64;
+            if let Some(last) = stream.last_transit {
+                let d = (transit - last).abs();
+                stream.jitter += (d - stream.jitter) / 16.0;
+            }
+            stream.last_transit = Some(transit);
+        }
+
+        let expected = (stream.max_seq - stream.first_seq) as u64 + 1;
+        let lost = expected.saturating_sub(stream.received);
+        let codec = codec_name(payload_type);
+
+        let mut details = format!(
+            "Type:      RTP\nSSRC:      0x{:08X}\nPayload:   {} ({})\nSequence:  {}\nTimestamp: {}\nMarker:    {}\nLoss:      {}/{} ({:.1}%)\n",
+            ssrc,
+            codec,
+            payload_type,
+            seq,
+            timestamp,
+            payload[1] & 0x80 != 0,
+            lost,
+            expected,
+            lost as f64 * 100.0 / expected as f64
+        );
+        match clock_rate {
+            Some(rate) => details.push_str(&format!(
+                "Jitter:    {:.2} ms\n",
+                stream.jitter * 1000.0 / rate as f64
+            )),
+            None => details.push_str("Jitter:    n/a (unknown clock rate)\n"),
+        }
+
+        Some(RtpInfo {
+            label: format!("RTP {}", codec),
+            details,
+        })
+    }
+}
+
+/// Static payload types from RFC 3551; dynamic ones (96-127) are negotiated in SDP.
+fn codec_name(payload_type: u8) -> String {
+    match payload_type {
+        0 => "G.711u".into(),
+        3 => "GSM".into(),
+        4 => "G.723".into(),
+        8 => "G.711a".into(),
+        9 => "G.722".into(),
+        13 => "CN".into(),
+        18 => "G.729".into(),
+        26 => "JPEG".into(),
+        31 => "H.261".into(),
+        34 => "H.263".into(),
+        96..=127 => format!("dyn{}", payload_type),
+        other => format!("PT{}", other),
+    }
+}
+
+fn clock_rate(payload_type: u8) -> Option<u32> {
+    match payload_type {
+        0 | 3 | 4 | 8 | 9 | 13 | 18 => Some(8000),
+        26 | 31 | 34 => Some(90000),
+        _ => None,
+    }
+}
@@ -0,0 +1,74 @@
+//! Raw mode and the alternate screen for the TUI. Leaving them is tied to dropping
+//! [`Screen`] and to the panic hook, so an error or panic past this point still hands
+//! the shell back in a usable state with the message readable.
+
+use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute,
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+};
+use ratatui::{Terminal, backend::CrosstermBackend};
+use std::io::{self, Stdout};
+use std::ops::{Deref, DerefMut};
+use std::panic;
+use std::sync::Once;
+
+static PANIC_HOOK: Once = Once::new();
+
+/// The TUI's terminal; restores the normal screen when dropped.
+pub struct Screen(Terminal<CrosstermBackend<Stdout>>);
+
+/// Switches to raw mode and the alternate screen, with mouse reporting if `mouse`.
+pub fn enter(mouse: bool) -> io::Result<Screen> {
+    PANIC_HOOK.call_once(|| {
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            // Before the message, or it lands on the alternate screen and is lost
+            restore();
+            default_hook(info);
+        }));
+    });
+
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    let entered = if mouse {
+        execute!(stdout, EnterAlternateScreen, EnableMouseCapture)
+    } else {
+        execute!(stdout, EnterAlternateScreen)
+    };
+    match entered.and_then(|_| Terminal::new(CrosstermBackend::new(stdout))) {
+        Ok(terminal) => Ok(Screen(terminal)),
+        Err(e) => {
+            restore();
+            Err(e)
+        }
+    }
+}
+
+/// Best effort: this runs on the way out of errors and panics, where a second failure
+/// has nowhere to be reported.
+fn restore() {
+    let _ = disable_raw_mode();
+    let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture);
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        restore();
+        let _ = self.0.show_cursor();
+    }
+}
+
+impl Deref for Screen {
+    type Target = Terminal<CrosstermBackend<Stdout>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Screen {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
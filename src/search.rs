@@ -0,0 +1,123 @@
+//! The `/` search language. A query made only of `key:value` terms (`port:443`,
+//! `proto:dns`, `host:192.168.1.5`, `app:firefox`) must match every term; anything
+//! else is a case-insensitive substring search, as before.
+
+use crate::ConnectionKey;
+use crate::capture::PacketData;
+
+enum Term {
+    /// Either TCP/UDP port
+    Port(u16),
+    /// Start of the protocol label ("smb" matches SMB2), or the transport
+    Proto(String),
+    /// Source or destination address, exactly
+    Host(String),
+    /// Part of the process name
+    App(String),
+}
+
+impl Term {
+    fn parse(word: &str) -> Option<Self> {
+        let (key, value) = word.split_once(':')?;
+        if value.is_empty() {
+            return None;
+        }
+        Some(match key {
+            "port" => Term::Port(value.parse().ok()?),
+            "proto" => Term::Proto(value.to_string()),
+            "host" => Term::Host(value.to_string()),
+            "app" => Term::App(value.to_string()),
+            _ => return None,
+        })
+    }
+
+    fn matches_packet(&self, packet: &PacketData) -> bool {
+        match self {
+            Term::Port(port) => packet
+                .ports
+                .is_some_and(|(src, dst)| src == *port || dst == *port),
+            Term::Proto(proto) => {
+                packet
+                    .proto_label
+                    .to_lowercase()
+                    .starts_with(proto.as_str())
+                    || packet
+                        .transport
+                        .is_some_and(|t| t.eq_ignore_ascii_case(proto))
+            }
+            Term::Host(host) => {
+                packet.source.eq_ignore_ascii_case(host) || packet.dest.eq_ignore_ascii_case(host)
+            }
+            Term::App(app) => packet.app_name.to_lowercase().contains(app.as_str()),
+        }
+    }
+
+    /// Rows carry ports only when grouped by port or flow; `port:` matches none
+    /// otherwise.
+    fn matches_connection(&self, (source, dest, proto, app): &ConnectionKey) -> bool {
+        match self {
+            Term::Port(port) => [source, dest]
+                .iter()
+                .any(|end| split_port(end).1 == Some(port.to_string().as_str())),
+            Term::Proto(wanted) => proto.to_lowercase().starts_with(wanted.as_str()),
+            Term::Host(host) => [source, dest]
+                .iter()
+                .any(|end| split_port(end).0.eq_ignore_ascii_case(host)),
+            Term::App(wanted) => app.to_lowercase().contains(wanted.as_str()),
+        }
+    }
+}
+
+/// A parsed search box. An empty query matches everything.
+pub struct SearchQuery {
+    /// `Some` when every word was a `key:value` term
+    terms: Option<Vec<Term>>,
+    /// Lowercased query, for the substring fallback
+    text: String,
+}
+
+impl SearchQuery {
+    pub fn parse(query: &str) -> Self {
+        let text = query.trim().to_lowercase();
+        let terms = text
+            .split_whitespace()
+            .map(Term::parse)
+            .collect::<Option<Vec<_>>>()
+            .filter(|terms| !terms.is_empty());
+        Self { terms, text }
+    }
+
+    pub fn matches_packet(&self, packet: &PacketData) -> bool {
+        match &self.terms {
+            Some(terms) => terms.iter().all(|term| term.matches_packet(packet)),
+            None => {
+                self.text.is_empty()
+                    || packet.summary.to_lowercase().contains(&self.text)
+                    || packet.app_name.to_lowercase().contains(&self.text)
+            }
+        }
+    }
+
+    pub fn matches_connection(&self, key: &ConnectionKey) -> bool {
+        match &self.terms {
+            Some(terms) => terms.iter().all(|term| term.matches_connection(key)),
+            None => {
+                self.text.is_empty() || format!("{:?}", key).to_lowercase().contains(&self.text)
+            }
+        }
+    }
+}
+
+/// "1.2.3.4:80" / "[::1]:443" into address and port; a bare address has no port.
+fn split_port(end: &str) -> (&str, Option<&str>) {
+    if let Some(rest) = end.strip_prefix('[')
+        && let Some((addr, port)) = rest.split_once("]:")
+    {
+        return (addr, Some(port));
+    }
+    match end.split_once(':') {
+        // More than one colon without brackets is a bare IPv6 address
+        Some((addr, port)) if !port.contains(':') => (addr, Some(port)),
+        _ => (end, None),
+    }
+}
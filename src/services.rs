@@ -0,0 +1,52 @@
+/// Well-known service names for common ports, shared by the port histogram and anything
+/// else that wants a human name for a port number.
+const SERVICES: &[(u16, &str)] = &[
+    (20, "ftp-data"),
+    (21, "ftp"),
+    (22, "ssh"),
+    (23, "telnet"),
+    (25, "smtp"),
+    (53, "dns"),
+    (67, "dhcp"),
+    (68, "dhcp"),
+    (80, "http"),
+    (110, "pop3"),
+    (123, "ntp"),
+    (137, "netbios-ns"),
+    (138, "netbios-dgm"),
+    (139, "netbios-ssn"),
+    (143, "imap"),
+    (161, "snmp"),
+    (389, "ldap"),
+    (443, "https"),
+    (445, "smb"),
+    (465, "smtps"),
+    (514, "syslog"),
+    (587, "submission"),
+    (636, "ldaps"),
+    (853, "dns-tls"),
+    (993, "imaps"),
+    (995, "pop3s"),
+    (1194, "openvpn"),
+    (1433, "mssql"),
+    (1900, "ssdp"),
+    (3306, "mysql"),
+    (3389, "rdp"),
+    (5060, "sip"),
+    (5353, "mdns"),
+    (5355, "llmnr"),
+    (5432, "postgres"),
+    (5900, "vnc"),
+    (6379, "redis"),
+    (8080, "http-alt"),
+    (8443, "https-alt"),
+    (27017, "mongodb"),
+    (51820, "wireguard"),
+];
+
+pub fn service_name(port: u16) -> Option<&'static str> {
+    SERVICES
+        .iter()
+        .find(|(p, _)| *p == port)
+        .map(|(_, name)| *name)
+}
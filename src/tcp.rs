@@ -0,0 +1,120 @@
+use etherparse::{TcpOptionElement, TcpSlice};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Handshakes not followed by any traffic for this long are forgotten.
+const FLOW_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Directed endpoint pair: (source, source port, destination, destination port).
+type FlowKey = (String, u16, String, u16);
+
+/// Window-scale option one side sent in its SYN.
+struct Flow {
+    shift: Option<u8>,
+    last_seen: Instant,
+}
+
+/// A segment's receive window, with the handshake's scale factor applied when known.
+pub struct WindowInfo {
+    pub raw: u16,
+    /// `raw` shifted by the negotiated factor; `None` if the handshake wasn't seen
+    pub scaled: Option<u32>,
+    pub shift: u8,
+    /// Receiver has no buffer space left and is stalling the sender
+    pub zero: bool,
+}
+
+impl WindowInfo {
+    pub fn details(&self) -> String {
+        let mut line = match self.scaled {
+            Some(scaled) if self.shift > 0 => format!(
+                "Window: {} (x{} = {})\n",
+                self.raw,
+                1u32 << self.shift,
+                scaled
+            ),
+            Some(_) => format!("Window: {}\n", self.raw),
+            None => format!("Window: {} (scale unknown, handshake not seen)\n", self.raw),
+        };
+        if self.zero {
+            line.push_str("*** ZERO WINDOW: receiver stalled ***\n");
+        }
+        line
+    }
+}
+
+/// Remembers each side's window-scale option from the SYNs, since later segments
+/// carry only the unscaled 16-bit field.
+pub struct WindowTracker {
+    flows: HashMap<FlowKey, Flow>,
+}
+
+impl WindowTracker {
+    pub fn new() -> Self {
+        Self {
+            flows: HashMap::new(),
+        }
+    }
+
+    pub fn observe(
+        &mut self,
+        source: &str,
+        dest: &str,
+        tcp: &TcpSlice,
+        now: Instant,
+    ) -> WindowInfo {
+        let key = (
+            source.to_string(),
+            tcp.source_port(),
+            dest.to_string(),
+            tcp.destination_port(),
+        );
+        let raw = tcp.window_size();
+
+        if tcp.syn() {
+            let shift = tcp.options_iterator().find_map(|option| match option {
+                // RFC 7323 caps the shift at 14
+                Ok(TcpOptionElement::WindowScale(shift)) => Some(shift.min(14)),
+                _ => None,
+            });
+            self.flows
+                .retain(|_, flow| now.duration_since(flow.last_seen) < FLOW_TIMEOUT);
+            self.flows.insert(
+                key,
+                Flow {
+                    shift,
+                    last_seen: now,
+                },
+            );
+            // The window in a SYN is never scaled
+            return WindowInfo {
+                raw,
+                scaled: Some(raw as u32),
+                shift: 0,
+                zero: false,
+            };
+        }
+
+        let reverse = (key.2.clone(), key.3, key.0.clone(), key.1);
+        let peer_shift = self.flows.get(&reverse).map(|flow| flow.shift);
+        let scaled = match (self.flows.get_mut(&key), peer_shift) {
+            (Some(flow), Some(peer_shift)) => {
+                flow.last_seen = now;
+                // Scaling is only in effect when both SYNs offered it
+                let shift = match (flow.shift, peer_shift) {
+                    (Some(shift), Some(_)) => shift,
+                    _ => 0,
+                };
+                Some((shift, (raw as u32) << shift))
+            }
+            _ => None,
+        };
+
+        WindowInfo {
+            raw,
+            scaled: scaled.map(|(_, window)| window),
+            shift: scaled.map_or(0, |(shift, _)| shift),
+            zero: raw == 0 && !tcp.rst(),
+        }
+    }
+}
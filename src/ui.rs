@@ -1,14 +1,263 @@
-use crate::capture::PacketData;
-use crate::{InputMode, Tab};
+use crate::capture::{PacketData, is_high_priority};
+use crate::diff::ConnectionDiff;
+use crate::keymap::{Action, KeyMap};
+use crate::latency::{SLOW_LOOKUP, SlowLookup};
+use crate::search::SearchQuery;
+use crate::services::service_name;
+use crate::{ConnectionKey, ConnectionStats, InputMode, Tab};
+use chrono::{Local, TimeDelta};
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Style, Stylize},
     text::{Line, Span},
-    widgets::{BarChart, Block, Borders, List, ListItem, ListState, Paragraph, Tabs, Wrap},
+    widgets::{
+        Bar, BarChart, BarGroup, Block, Borders, Clear, List, ListItem, ListState, Paragraph, Tabs,
+        Wrap,
+    },
 };
-use std::collections::HashMap;
-use std::time::Instant;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+/// Below this many columns the list/detail panes are stacked instead of side by side.
+const COMPACT_WIDTH: u16 = 100;
+
+/// How the throughput chart maps bytes/s to bar height.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChartScale {
+    /// Tallest visible bar fills the chart (ratatui's default)
+    Auto,
+    /// Fixed ceiling in bytes/s, so steady traffic keeps its proportion
+    Fixed(u64),
+    /// log10 of the value, for wide dynamic ranges
+    Log,
+}
+
+impl ChartScale {
+    pub fn next(self, ceiling: u64) -> Self {
+        match self {
+            ChartScale::Auto => ChartScale::Fixed(ceiling),
+            ChartScale::Fixed(_) => ChartScale::Log,
+            ChartScale::Log => ChartScale::Auto,
+        }
+    }
+}
+
+/// Column the Connections list is ordered by.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortKey {
+    Bytes,
+    App,
+    Protocol,
+    Source,
+}
+
+/// Connections ordering, kept across redraws and shared with the input handler so
+/// selection indexes line up with the rows on screen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConnectionSort {
+    pub key: SortKey,
+    pub descending: bool,
+}
+
+impl Default for ConnectionSort {
+    /// Biggest talkers first
+    fn default() -> Self {
+        Self {
+            key: SortKey::Bytes,
+            descending: true,
+        }
+    }
+}
+
+impl ConnectionSort {
+    /// Next column, in its natural direction: bytes largest first, text A→Z.
+    pub fn next_key(self) -> Self {
+        let key = match self.key {
+            SortKey::Bytes => SortKey::App,
+            SortKey::App => SortKey::Protocol,
+            SortKey::Protocol => SortKey::Source,
+            SortKey::Source => SortKey::Bytes,
+        };
+        Self {
+            key,
+            descending: key == SortKey::Bytes,
+        }
+    }
+
+    pub fn reversed(self) -> Self {
+        Self {
+            descending: !self.descending,
+            ..self
+        }
+    }
+
+    pub fn name(self) -> String {
+        let key = match self.key {
+            SortKey::Bytes => "bytes",
+            SortKey::App => "app",
+            SortKey::Protocol => "protocol",
+            SortKey::Source => "source",
+        };
+        format!("{} {}", key, if self.descending { "↓" } else { "↑" })
+    }
+}
+
+/// Units for throughput readouts; the history itself is always in bytes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RateUnit {
+    /// "1.20 MB/s", binary multiples like the rest of the UI
+    Bytes,
+    /// "9.60 Mbps", decimal multiples like link speeds
+    Bits,
+}
+
+impl RateUnit {
+    pub fn toggle(self) -> Self {
+        match self {
+            RateUnit::Bytes => RateUnit::Bits,
+            RateUnit::Bits => RateUnit::Bytes,
+        }
+    }
+
+    fn format(self, bytes_per_sec: u64) -> String {
+        match self {
+            RateUnit::Bytes => format!("{}/s", format_bytes(bytes_per_sec)),
+            RateUnit::Bits => format_bits(bytes_per_sec * 8),
+        }
+    }
+}
+
+/// What the feed inspector shows for the selected (or pinned) packet.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DetailView {
+    /// One-screen overview: addresses, protocol, app, size
+    Summary,
+    /// Decoded layers followed by the hex dump (or the text of plaintext protocols)
+    Details,
+    /// Hex + ASCII dump only
+    Hex,
+    /// Retained frame bytes as plain hex, for pasting into other decoders
+    Raw,
+}
+
+impl DetailView {
+    /// `Raw` is skipped unless frames are being retained.
+    pub fn next(self, raw_available: bool) -> Self {
+        match self {
+            DetailView::Summary => DetailView::Details,
+            DetailView::Details => DetailView::Hex,
+            DetailView::Hex if raw_available => DetailView::Raw,
+            DetailView::Hex | DetailView::Raw => DetailView::Summary,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DetailView::Summary => "summary",
+            DetailView::Details => "details",
+            DetailView::Hex => "hex",
+            DetailView::Raw => "raw",
+        }
+    }
+}
+
+/// Inspector text for `packet` in `view`. Plaintext protocols show their payload as
+/// lines, cut at `text_limit` characters, where the details view would have the hex.
+fn inspector_text(packet: &PacketData, view: DetailView, text_limit: usize) -> String {
+    match view {
+        DetailView::Summary => {
+            let mut text = format!(
+                "Time:      {}\nApp:       {}\nProtocol:  {}\nSource:    {}\nDest:      {}\nLength:    {}\n",
+                packet.time_label,
+                packet.app_name,
+                packet.proto_label,
+                packet.source,
+                packet.dest,
+                format_bytes(packet.length as u64)
+            );
+            if let Some((src, dst)) = packet.ports {
+                text.push_str(&format!("Ports:     {} -> {}\n", src, dst));
+            }
+            if let Some(transport) = packet.transport {
+                text.push_str(&format!("Transport: {}\n", transport));
+            }
+            if let Some(country) = &packet.country {
+                text.push_str(&format!("Country:   {}\n", country));
+            }
+            text
+        }
+        DetailView::Details => match &packet.text {
+            Some(text) => {
+                let shown: String = text.chars().take(text_limit).collect();
+                let cut = text.chars().count().saturating_sub(text_limit);
+                let mut out = format!(
+                    "{}\n\n--- PAYLOAD (TEXT) ---\n{}",
+                    packet.full_details,
+                    shown.trim_end()
+                );
+                if cut > 0 {
+                    out.push_str(&format!("\n… {} more characters", cut));
+                }
+                out
+            }
+            None => format!(
+                "{}\n\n--- RAW PAYLOAD (HEX) ---\n{}",
+                packet.full_details, packet.hex_dump
+            ),
+        },
+        DetailView::Hex => packet.hex_dump.clone(),
+        DetailView::Raw => match &packet.raw {
+            Some(raw) => raw
+                .chunks(32)
+                .map(|chunk| {
+                    chunk
+                        .iter()
+                        .map(|b| format!("{:02x}", b))
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+            // Evicted by the --retain-bytes budget
+            None => "Raw bytes no longer retained for this packet.".into(),
+        },
+    }
+}
+
+/// Bar height for the log scale; two decimal places of log10 keep small steps visible.
+fn log_height(bytes: u64) -> u64 {
+    ((bytes as f64 + 1.0).log10() * 100.0) as u64
+}
+
+/// Dashes across the empty cells of the chart at `value`'s height, so the rolling
+/// average reads as a line behind the bars.
+fn draw_reference_line(f: &mut Frame, chart_area: Rect, value: u64, max: u64) {
+    // Inside the border, minus the bar-label row at the bottom
+    let bars_height = chart_area.height.saturating_sub(3);
+    if value == 0 || bars_height == 0 {
+        return;
+    }
+    let rows = (value * bars_height as u64 / max.max(1)).min(bars_height as u64 - 1) as u16;
+    let y = chart_area.y + bars_height - rows;
+    let buf = f.buffer_mut();
+    for x in chart_area.x + 1..chart_area.right().saturating_sub(1) {
+        let cell = &mut buf[(x, y)];
+        if cell.symbol() == " " {
+            cell.set_symbol("┄").set_fg(Color::Yellow);
+        }
+    }
+}
+
+/// Mean of the last `window` seconds of throughput (fewer if the history is shorter).
+fn rolling_average(history: &[u64], window: usize) -> u64 {
+    let recent = &history[history.len().saturating_sub(window)..];
+    if recent.is_empty() {
+        0
+    } else {
+        recent.iter().sum::<u64>() / recent.len() as u64
+    }
+}
 
 fn format_bytes(bytes: u64) -> String {
     if bytes < 1024 {
@@ -20,21 +269,86 @@ fn format_bytes(bytes: u64) -> String {
     }
 }
 
+fn format_bits(bits_per_sec: u64) -> String {
+    if bits_per_sec < 1000 {
+        format!("{} bps", bits_per_sec)
+    } else if bits_per_sec < 1_000_000 {
+        format!("{:.2} Kbps", bits_per_sec as f64 / 1e3)
+    } else if bits_per_sec < 1_000_000_000 {
+        format!("{:.2} Mbps", bits_per_sec as f64 / 1e6)
+    } else {
+        format!("{:.2} Gbps", bits_per_sec as f64 / 1e9)
+    }
+}
+
+/// Connection endpoint whose ephemeral port was folded to `*`.
+fn is_folded(endpoint: &str) -> bool {
+    endpoint.ends_with(":*")
+}
+
+/// `HH:MM:SS`, with hours running past 24 for very long sessions.
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// Returns how many rows the active tab's list shows, so the input handler can page.
+#[allow(clippy::too_many_arguments)]
 pub fn draw(
     f: &mut Frame,
     active_tab: Tab,
-    local_packets: &[PacketData],
-    connections: &HashMap<(String, String, String, String), u64>,
+    local_packets: &VecDeque<PacketData>,
+    connections: &HashMap<ConnectionKey, ConnectionStats>,
+    remote_hosts: usize,
+    baseline: Option<&HashSet<ConnectionKey>>,
     throughput_history: &[u64],
     paused: &bool,
+    paused_backlog: usize,
+    backlog_dropped: u64,
     is_saving: &bool,
     filter: &str,
+    app_filter: Option<&str>,
+    enabled_chips: &HashSet<&str>,
     mode: &InputMode,
     feed_list_state: &mut ListState,
     connections_list_state: &mut ListState,
     selected_spike_idx: Option<usize>,
     pause_time: Option<Instant>,
-) {
+    capture_stats: Option<pcap::Stat>,
+    show_detail: bool,
+    keymap: &KeyMap,
+    chart_scale: ChartScale,
+    rate_unit: RateUnit,
+    connection_sort: ConnectionSort,
+    show_length: bool,
+    port_counts: Option<&HashMap<u16, u64>>,
+    app_bytes: Option<&HashMap<String, u64>>,
+    proto_bytes: Option<&HashMap<String, u64>>,
+    slow_dns: Option<&VecDeque<SlowLookup>>,
+    pending_clear: Option<Action>,
+    new_conn_rate: u64,
+    new_conn_alert: Option<u64>,
+    status_message: Option<&str>,
+    source_summary: &str,
+    avg_window: usize,
+    throughput_peak: u64,
+    process_warning: Option<&str>,
+    capture_down: Option<&str>,
+    memory: Option<(usize, usize)>,
+    filter_matches: usize,
+    spike_absolute_time: bool,
+    capture_filter: Option<(&str, u64)>,
+    pinned_packet: Option<&PacketData>,
+    detail_view: DetailView,
+    ring_file: Option<(usize, usize)>,
+    text_limit: usize,
+    host_names: Option<&HashMap<IpAddr, String>>,
+    frames_dropped: u64,
+    queue_dropped: u64,
+    input_ended: bool,
+) -> usize {
+    let compact = f.area().width < COMPACT_WIDTH;
+
     let main_chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -51,33 +365,73 @@ pub fn draw(
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title(" NET-SNIFF-RS "),
+                    .title(" NET-SNIFF-RS ")
+                    .title(
+                        Line::from(format!(" {} ", source_summary))
+                            .right_aligned()
+                            .fg(Color::DarkGray),
+                    ),
             )
             .select(active_tab as usize)
             .highlight_style(Style::default().fg(Color::Yellow).bold()),
         main_chunks[0],
     );
 
-    match active_tab {
+    let page_rows = match active_tab {
         Tab::Feed => draw_feed_tab(
             f,
             main_chunks[1],
             local_packets,
             filter,
+            app_filter,
+            enabled_chips,
+            keymap,
             feed_list_state,
             selected_spike_idx,
             throughput_history,
             pause_time,
+            compact,
+            show_detail,
+            show_length,
+            spike_absolute_time,
+            pinned_packet,
+            detail_view,
+            text_limit,
+            rate_unit,
+            host_names,
         ),
         Tab::Connections => draw_connections_tab(
             f,
             main_chunks[1],
             connections,
+            remote_hosts,
+            baseline,
             throughput_history,
             filter,
+            app_filter,
             connections_list_state,
             selected_spike_idx,
+            chart_scale,
+            rate_unit,
+            connection_sort,
+            avg_window,
+            throughput_peak,
+            compact,
+            show_detail,
         ),
+    };
+
+    if let Some(port_counts) = port_counts {
+        draw_port_histogram(f, main_chunks[1], port_counts);
+    }
+    if let Some(app_bytes) = app_bytes {
+        draw_byte_ranking(f, main_chunks[1], " BANDWIDTH BY PROCESS ", app_bytes);
+    }
+    if let Some(proto_bytes) = proto_bytes {
+        draw_byte_ranking(f, main_chunks[1], " TRAFFIC BY PROTOCOL ", proto_bytes);
+    }
+    if let Some(slow_dns) = slow_dns {
+        draw_slow_dns(f, main_chunks[1], slow_dns);
     }
 
     // --- DYNAMIC FOOTER ---
@@ -85,10 +439,10 @@ pub fn draw(
         Span::styled(
             format!(" {:?} ", mode),
             Style::default()
-                .bg(if *mode == InputMode::Normal {
-                    Color::Blue
-                } else {
-                    Color::Magenta
+                .bg(match mode {
+                    InputMode::Normal => Color::Blue,
+                    InputMode::Search => Color::Magenta,
+                    InputMode::Confirm => Color::Red,
                 })
                 .fg(Color::Black)
                 .bold(),
@@ -96,25 +450,220 @@ pub fn draw(
         " ".into(),
     ];
 
+    // The search text, and how selective it is over the buffered packets
+    if *mode == InputMode::Search || !filter.is_empty() {
+        status_line.push(Span::styled(
+            format!("/{} ", filter),
+            Style::default().fg(Color::Magenta),
+        ));
+        if !filter.is_empty() {
+            status_line.push(Span::styled(
+                format!("{}/{} match ", filter_matches, local_packets.len()),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+    }
+
+    // Unlike the search, this one throws packets away; it must not look like a view
+    if let Some((text, dropped)) = capture_filter {
+        status_line.push(Span::styled(
+            format!(" CAPTURE FILTER \"{}\": {} dropped ", text, dropped),
+            Style::default().bg(Color::Red).fg(Color::White).bold(),
+        ));
+        status_line.push(" ".into());
+    }
+
     if let Some(_idx) = selected_spike_idx {
         status_line.push(Span::styled(
             " INSPECTOR MODE ",
             Style::default().bg(Color::Yellow).fg(Color::Black).bold(),
         ));
+    } else if let Some(reason) = capture_down {
+        status_line.push(" INTERFACE DOWN ".on_red().white().bold());
+        status_line.push(Span::styled(
+            format!(" reconnecting… ({}) ", reason),
+            Style::default().fg(Color::Red),
+        ));
+    } else if *paused {
+        status_line.push(" PAUSED ".on_red().white().bold());
+    } else if input_ended {
+        // Everything in the file is on screen; nothing more is coming
+        status_line.push(" EOF ".on_blue().white().bold());
+        status_line.push(Span::styled(
+            " end of capture file ",
+            Style::default().fg(Color::Blue),
+        ));
+    } else {
+        status_line.push(" LIVE ".on_green().white().bold());
+    }
+
+    // Shown under the spike inspector too, which is where pausing usually leads
+    if *paused && paused_backlog > 0 {
+        status_line.push(Span::styled(
+            format!(" {} packets waiting ", paused_backlog),
+            Style::default().fg(Color::Red),
+        ));
+    }
+    if *paused && backlog_dropped > 0 {
+        status_line.push(Span::styled(
+            format!(" backlog full, {} dropped ", backlog_dropped),
+            Style::default().fg(Color::Red).bold(),
+        ));
+    }
+
+    if let Some(app) = app_filter {
+        status_line.push(" ".into());
+        status_line.push(Span::styled(
+            format!(" APP: {} ", app),
+            Style::default().bg(Color::Green).fg(Color::Black).bold(),
+        ));
+    }
+
+    // A burst of first-seen connections hints at scanning or a runaway client
+    let new_conn_style = if new_conn_alert.is_some_and(|limit| new_conn_rate >= limit) {
+        Style::default().bg(Color::Red).fg(Color::White).bold()
     } else {
-        status_line.push(if *paused {
-            " PAUSED ".on_red().white().bold()
+        Style::default().fg(Color::DarkGray)
+    };
+    status_line.push(" ".into());
+    status_line.push(Span::styled(
+        format!(" new conn {}/s ", new_conn_rate),
+        new_conn_style,
+    ));
+
+    if let Some(message) = status_message {
+        status_line.push(" ".into());
+        status_line.push(Span::styled(
+            format!(" {} ", message),
+            Style::default().fg(Color::Yellow).bold(),
+        ));
+    }
+
+    // Which slot of the --ring-files rotation is being written
+    if let Some((index, count)) = ring_file {
+        status_line.push(" ".into());
+        status_line.push(Span::styled(
+            format!(" REC ring {}/{} ", index, count),
+            Style::default().bg(Color::Red).fg(Color::White).bold(),
+        ));
+    }
+
+    // Estimated buffer usage against --max-mem, red once eviction kicks in
+    if let Some((used, budget)) = memory {
+        let style = if used * 10 >= budget * 9 {
+            Style::default().fg(Color::Red).bold()
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        status_line.push(Span::styled(
+            format!(
+                " mem {}/{} ",
+                format_bytes(used as u64),
+                format_bytes(budget as u64)
+            ),
+            style,
+        ));
+    }
+
+    // Explains why every app name reads "Unknown"
+    if let Some(reason) = process_warning {
+        status_line.push(" ".into());
+        status_line.push(Span::styled(
+            format!("process resolution unavailable ({})", reason),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+
+    // Frames the capture thread threw away because the parser wasn't keeping up
+    if frames_dropped > 0 {
+        status_line.push(" ".into());
+        status_line.push(Span::styled(
+            format!(" dropped {} (parser behind) ", frames_dropped),
+            Style::default().fg(Color::Red).bold(),
+        ));
+    }
+
+    // Packets the parser threw away because the UI wasn't draining its queue
+    if queue_dropped > 0 {
+        status_line.push(" ".into());
+        status_line.push(Span::styled(
+            format!(" dropped {} (UI behind) ", queue_dropped),
+            Style::default().fg(Color::Red).bold(),
+        ));
+    }
+
+    // Kernel counters: a rising drop count means the analysis is missing packets
+    if let Some(stat) = capture_stats {
+        let drop_style = if stat.dropped > 0 || stat.if_dropped > 0 {
+            Style::default().fg(Color::Red).bold()
         } else {
-            " LIVE ".on_green().white().bold()
-        });
+            Style::default().fg(Color::DarkGray)
+        };
+        status_line.push(" ".into());
+        status_line.push(Span::styled(
+            format!(
+                " rx {} / drop {} / ifdrop {} ",
+                stat.received, stat.dropped, stat.if_dropped
+            ),
+            drop_style,
+        ));
     }
 
-    let mut hints = vec!["[q] Quit", "[1/2] Tabs"];
+    let key = |action| keymap.label(action);
+    let mut hints = vec![
+        format!("[{}] Quit", key(Action::Quit)),
+        format!(
+            "[{}/{}] Tabs",
+            key(Action::FeedTab),
+            key(Action::ConnectionsTab)
+        ),
+    ];
+    if *mode == InputMode::Confirm {
+        // The question replaces the hints so it can't be missed
+        hints = vec![match pending_clear {
+            Some(Action::ClearConnections) => "Clear all connection totals? (y/n)".to_string(),
+            _ => "Clear all captured packets? (y/n)".to_string(),
+        }];
+    }
     if *mode == InputMode::Normal {
-        hints.push("[/] Search");
-        hints.push("[Space] Pause");
+        hints.push(format!("[{}] Search", key(Action::Search)));
+        if !filter.is_empty() || capture_filter.is_some() {
+            hints.push(format!("[{}] Capture Filter", key(Action::PromoteFilter)));
+        }
+        hints.push(format!("[{}] Pause", key(Action::TogglePause)));
+        hints.push(format!("[{}] App Filter", key(Action::FilterApp)));
+        hints.push(format!("[{}] Ports", key(Action::TogglePorts)));
+        hints.push(format!("[{}] Apps", key(Action::ToggleApps)));
+        hints.push(format!("[{}] Protocols", key(Action::ToggleProtocols)));
+        hints.push(format!("[{}] Slow DNS", key(Action::ToggleSlowDns)));
         if *paused {
-            hints.push("[←/→] Scrub Spike");
+            hints.push(format!(
+                "[{}/{}] Scrub Spike",
+                key(Action::SpikeLeft),
+                key(Action::SpikeRight)
+            ));
+        }
+        if active_tab == Tab::Feed {
+            hints.push(format!("[{}] Length", key(Action::ToggleLength)));
+            hints.push(format!("[{}] Pin", key(Action::PinInspector)));
+            hints.push(format!("[{}] View", key(Action::CycleDetailView)));
+            hints.push(format!("[{}] Hostnames", key(Action::ToggleHostnames)));
+        }
+        if active_tab == Tab::Connections {
+            hints.push(format!("[{}] Scale", key(Action::CycleChartScale)));
+            hints.push(format!("[{}] Bits/Bytes", key(Action::ToggleRateUnit)));
+            hints.push(format!("[{}] Baseline", key(Action::SnapshotConnections)));
+            hints.push(format!("[{}] Group", key(Action::CycleGranularity)));
+            hints.push(format!(
+                "[{}/{}] Sort",
+                key(Action::CycleSort),
+                key(Action::ReverseSort)
+            ));
+            hints.push(format!("[{}] Fold Ports", key(Action::ToggleEphemeralFold)));
+            hints.push(format!("[{}] Export", key(Action::ExportConnections)));
+        }
+        if compact {
+            hints.push(format!("[{}] Detail", key(Action::ToggleDetail)));
         }
     }
 
@@ -129,30 +678,305 @@ pub fn draw(
         ),
         main_chunks[2],
     );
+    page_rows
 }
 
-fn draw_feed_tab(
+/// `--diff` view: connections only in A, in both, and only in B, side by side.
+pub fn draw_diff(
     f: &mut Frame,
-    area: Rect,
-    packets: &[PacketData],
-    filter: &str,
-    list_state: &mut ListState,
-    spike_idx: Option<usize>,
-    history: &[u64],
-    pause_time: Option<Instant>,
+    a_name: &str,
+    b_name: &str,
+    diff: &ConnectionDiff,
+    offset: usize,
+    keymap: &KeyMap,
 ) {
-    let chunks = Layout::default()
+    let main_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(5), Constraint::Length(1)])
+        .split(f.area());
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(33),
+            Constraint::Percentage(34),
+            Constraint::Percentage(33),
+        ])
+        .split(main_chunks[0]);
+
+    let describe = |key: &ConnectionKey| format!("{} {} -> {}", key.2, key.0, key.1);
+
+    let only_a: Vec<ListItem> = diff
+        .only_a
+        .iter()
+        .skip(offset)
+        .map(|(key, bytes)| {
+            ListItem::new(Line::from(vec![
+                Span::raw(describe(key)),
+                Span::raw(format!(" {}", format_bytes(*bytes))).cyan(),
+            ]))
+        })
+        .collect();
+    let common: Vec<ListItem> = diff
+        .common
+        .iter()
+        .skip(offset)
+        .map(|(key, bytes_a, bytes_b)| {
+            ListItem::new(Line::from(vec![
+                Span::raw(describe(key)),
+                Span::raw(format!(
+                    " {} / {}",
+                    format_bytes(*bytes_a),
+                    format_bytes(*bytes_b)
+                ))
+                .cyan(),
+            ]))
+        })
+        .collect();
+    let only_b: Vec<ListItem> = diff
+        .only_b
+        .iter()
+        .skip(offset)
+        .map(|(key, bytes)| {
+            ListItem::new(Line::from(vec![
+                Span::raw(describe(key)),
+                Span::raw(format!(" {}", format_bytes(*bytes))).cyan(),
+            ]))
+        })
+        .collect();
+
+    f.render_widget(
+        List::new(only_a).block(
+            Block::default()
+                .title(format!(" ONLY IN {} ({}) ", a_name, diff.only_a.len()))
+                .borders(Borders::ALL)
+                .red(),
+        ),
+        columns[0],
+    );
+    f.render_widget(
+        List::new(common).block(
+            Block::default()
+                .title(format!(" COMMON ({}) ", diff.common.len()))
+                .borders(Borders::ALL)
+                .green(),
+        ),
+        columns[1],
+    );
+    f.render_widget(
+        List::new(only_b).block(
+            Block::default()
+                .title(format!(" ONLY IN {} ({}) ", b_name, diff.only_b.len()))
+                .borders(Borders::ALL)
+                .yellow(),
+        ),
+        columns[2],
+    );
+
+    let key = |action| keymap.label(action);
+    f.render_widget(
+        Paragraph::new(format!(
+            " [{}] Quit | [{}/{}] Scroll ",
+            key(Action::Quit),
+            key(Action::Down),
+            key(Action::Up)
+        ))
+        .centered()
+        .dark_gray()
+        .italic(),
+        main_chunks[1],
+    );
+}
+
+/// Ranked destination ports, drawn as an overlay over whichever tab is active.
+fn draw_port_histogram(f: &mut Frame, area: Rect, port_counts: &HashMap<u16, u64>) {
+    let popup = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(20),
+            Constraint::Percentage(60),
+            Constraint::Percentage(20),
+        ])
+        .split(area)[1];
+
+    let mut ranked: Vec<(&u16, &u64)> = port_counts.iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+    ranked.truncate(popup.height.saturating_sub(2) as usize);
+
+    let max = ranked.first().map_or(1, |(_, count)| **count).max(1);
+    // Port (5) + service (12) + count (10) + separators
+    let bar_width = popup.width.saturating_sub(34) as u64;
+
+    let items: Vec<ListItem> = ranked
+        .iter()
+        .map(|(port, count)| {
+            let filled = (**count * bar_width).div_ceil(max) as usize;
+            ListItem::new(Line::from(vec![
+                Span::raw(format!("{:>5} ", port)).yellow(),
+                Span::raw(format!("{:<12}", service_name(**port).unwrap_or("-"))).green(),
+                Span::raw(format!(
+                    " {:<width$}",
+                    "█".repeat(filled),
+                    width = bar_width as usize
+                ))
+                .cyan(),
+                Span::raw(format!(" {:>10}", count)),
+            ]))
+        })
+        .collect();
+
+    f.render_widget(Clear, popup);
+    f.render_widget(
+        List::new(items).block(
+            Block::default()
+                .title(" DESTINATION PORTS (packets) ")
+                .borders(Borders::ALL)
+                .magenta(),
+        ),
+        popup,
+    );
+}
+
+/// Byte totals ranked largest first with each one's share of the whole: processes,
+/// whatever hosts they talk to, or protocol labels.
+fn draw_byte_ranking(f: &mut Frame, area: Rect, title: &str, totals: &HashMap<String, u64>) {
+    let popup = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(20),
+            Constraint::Percentage(60),
+            Constraint::Percentage(20),
+        ])
+        .split(area)[1];
+
+    let total = totals.values().sum::<u64>().max(1);
+    let mut ranked: Vec<(&String, &u64)> = totals.iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+    ranked.truncate(popup.height.saturating_sub(2) as usize);
+
+    // Name (16) + bytes (10) + share (7) + separators
+    let bar_width = popup.width.saturating_sub(39) as u64;
+
+    let items: Vec<ListItem> = ranked
+        .iter()
+        .map(|(name, bytes)| {
+            let filled = (**bytes * bar_width).div_ceil(total) as usize;
+            ListItem::new(Line::from(vec![
+                Span::raw(format!("{:<16.16}", name)).green(),
+                Span::raw(format!(" {:>10}", format_bytes(**bytes))).cyan(),
+                Span::raw(format!(" {:>5.1}%", **bytes as f64 * 100.0 / total as f64)).yellow(),
+                Span::raw(format!(
+                    " {:<width$}",
+                    "█".repeat(filled),
+                    width = bar_width as usize
+                ))
+                .cyan(),
+            ]))
+        })
+        .collect();
+
+    f.render_widget(Clear, popup);
+    f.render_widget(
+        List::new(items).block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .magenta(),
+        ),
+        popup,
+    );
+}
+
+/// Recent DNS lookups that took at least [`SLOW_LOOKUP`], newest first.
+fn draw_slow_dns(f: &mut Frame, area: Rect, slow_dns: &VecDeque<SlowLookup>) {
+    let popup = Layout::default()
         .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(20),
+            Constraint::Percentage(60),
+            Constraint::Percentage(20),
+        ])
+        .split(area)[1];
+
+    let items: Vec<ListItem> = if slow_dns.is_empty() {
+        vec![ListItem::new(
+            format!("No lookups slower than {} ms yet", SLOW_LOOKUP.as_millis()).dark_gray(),
+        )]
+    } else {
+        slow_dns
+            .iter()
+            .take(popup.height.saturating_sub(2) as usize)
+            .map(|lookup| {
+                ListItem::new(Line::from(vec![
+                    Span::raw(format!("{} ", lookup.time_label)).dark_gray(),
+                    Span::raw(format!("{:>6} ms ", lookup.latency.as_millis())).red(),
+                    Span::raw(lookup.name.clone()).white(),
+                    Span::raw(format!(" via {}", lookup.server)).dark_gray(),
+                ]))
+            })
+            .collect()
+    };
+
+    f.render_widget(Clear, popup);
+    f.render_widget(
+        List::new(items).block(
+            Block::default()
+                .title(" SLOW DNS ")
+                .borders(Borders::ALL)
+                .red(),
+        ),
+        popup,
+    );
+}
+
+/// Splits a tab body into its list and detail panes. Wide terminals get them side by
+/// side; compact ones stack the detail under the list, or drop it when there's nothing
+/// to show.
+fn split_list_detail(area: Rect, compact: bool, detail_visible: bool) -> (Rect, Option<Rect>) {
+    if compact && !detail_visible {
+        return (area, None);
+    }
+    let direction = if compact {
+        Direction::Vertical
+    } else {
+        Direction::Horizontal
+    };
+    let chunks = Layout::default()
+        .direction(direction)
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
         .split(area);
+    (chunks[0], Some(chunks[1]))
+}
+
+/// Rows a bordered list in `area` shows at once, for paging.
+fn list_rows(area: Rect) -> usize {
+    area.height.saturating_sub(2) as usize
+}
 
-    // Filter packets to the specific spike window
-    let filtered: Vec<&PacketData> = packets
+/// Packets the feed list shows, in display order. The input handler uses the same
+/// function so a selection index always points at the row the user sees.
+pub fn visible_packets<'a>(
+    packets: &'a VecDeque<PacketData>,
+    filter: &str,
+    app_filter: Option<&str>,
+    enabled_chips: &HashSet<&str>,
+    spike_idx: Option<usize>,
+    history_len: usize,
+    pause_time: Option<Instant>,
+) -> Vec<&'a PacketData> {
+    let query = SearchQuery::parse(filter);
+    packets
         .iter()
+        .filter(|p| app_filter.is_none_or(|app| p.app_name == app))
         .filter(|p| {
+            CHIPS
+                .iter()
+                .all(|chip| enabled_chips.contains(chip) || !chip_matches(chip, p))
+        })
+        .filter(|p| {
+            // While scrubbing, only show the selected one-second window
             if let Some(idx) = spike_idx {
                 if let Some(ref_time) = pause_time {
-                    let seconds_before_pause = (history.len().saturating_sub(1 + idx)) as u64;
+                    let seconds_before_pause = (history_len.saturating_sub(1 + idx)) as u64;
                     if p.timestamp > ref_time {
                         return false;
                     }
@@ -162,48 +986,321 @@ fn draw_feed_tab(
                     false
                 }
             } else {
-                filter.is_empty() || p.summary.to_lowercase().contains(&filter.to_lowercase())
+                query.matches_packet(p)
             }
         })
+        .collect()
+}
+
+/// Quick-filter chips above the feed, in display order; toggled by `ToggleChip(i)`.
+pub const CHIPS: [&str; 6] = ["TCP", "UDP", "DNS", "TLS", "HTTP", "ICMP"];
+
+/// Whether `packet` falls under `chip`. Switching a chip off hides everything it
+/// matches, so "TCP" off also hides TLS and HTTP; traffic no chip covers always shows.
+fn chip_matches(chip: &str, packet: &PacketData) -> bool {
+    let label = packet.proto_label.as_str();
+    match chip {
+        "TCP" | "UDP" | "ICMP" => packet.transport == Some(chip),
+        "DNS" => is_name_lookup(label),
+        "TLS" => label.starts_with("TLS") || label == "HTTPS",
+        "HTTP" => label.starts_with("HTTP") && label != "HTTPS",
+        _ => false,
+    }
+}
+
+/// Feed summary color, so a noisy feed can be scanned by protocol. Red and magenta
+/// are left to the warning and DSCP highlights that take precedence over these.
+fn protocol_color(proto_label: &str) -> Color {
+    match proto_label {
+        l if l.starts_with("TLS") || l == "HTTPS" => Color::LightBlue,
+        l if l.starts_with("HTTP") => Color::Yellow,
+        l if is_name_lookup(l) => Color::Cyan,
+        l if l.starts_with("ICMP") => Color::LightGreen,
+        "ARP" => Color::Gray,
+        _ => Color::White,
+    }
+}
+
+/// DNS over UDP or TCP, or its local-link cousins mDNS and LLMNR.
+fn is_name_lookup(proto_label: &str) -> bool {
+    proto_label.starts_with("DNS") || proto_label == "mDNS" || proto_label == "LLMNR"
+}
+
+fn chip_text(keymap: &KeyMap, index: usize) -> String {
+    format!(
+        " {} {} ",
+        keymap.label(Action::ToggleChip(index)),
+        CHIPS[index]
+    )
+}
+
+/// Index of the chip at terminal column `x` on the chip row, for mouse clicks.
+pub fn chip_at(keymap: &KeyMap, x: u16) -> Option<usize> {
+    // The row starts at the left edge of the terminal; chips are one space apart
+    let mut start = 0u16;
+    for index in 0..CHIPS.len() {
+        let width = chip_text(keymap, index).chars().count() as u16;
+        if (start..start + width).contains(&x) {
+            return Some(index);
+        }
+        start += width + 1;
+    }
+    None
+}
+
+/// Row the feed's chip bar is drawn on: the first line below the tab bar.
+pub const CHIP_ROW: u16 = 3;
+
+fn draw_chips(f: &mut Frame, area: Rect, keymap: &KeyMap, enabled_chips: &HashSet<&str>) {
+    let mut spans = Vec::new();
+    for (index, chip) in CHIPS.iter().enumerate() {
+        let style = if enabled_chips.contains(chip) {
+            Style::default().fg(Color::Black).bg(Color::Green)
+        } else {
+            Style::default().fg(Color::DarkGray).crossed_out()
+        };
+        spans.push(Span::styled(chip_text(keymap, index), style));
+        spans.push(Span::raw(" "));
+    }
+    f.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+/// The `/` search test: `key:value` terms or plain text (see [`SearchQuery`]). An
+/// empty filter matches.
+pub fn matches_text(packet: &PacketData, filter: &str) -> bool {
+    SearchQuery::parse(filter).matches_packet(packet)
+}
+
+/// Sessions the connections list shows, largest first.
+pub fn visible_connections<'a>(
+    connections: &'a HashMap<ConnectionKey, ConnectionStats>,
+    filter: &str,
+    app_filter: Option<&str>,
+    sort: ConnectionSort,
+) -> Vec<(&'a ConnectionKey, &'a ConnectionStats)> {
+    let query = SearchQuery::parse(filter);
+    let mut sorted: Vec<_> = connections
+        .iter()
+        .filter(|(key, _)| app_filter.is_none_or(|app| key.3 == app))
+        .filter(|(key, _)| query.matches_connection(key))
         .collect();
+    // Ties fall back to bytes, largest first, so equal names don't shuffle per frame
+    sorted.sort_by(|(a_key, a), (b_key, b)| {
+        let order = match sort.key {
+            SortKey::Bytes => a.bytes.cmp(&b.bytes),
+            SortKey::App => a_key.3.cmp(&b_key.3),
+            SortKey::Protocol => a_key.2.cmp(&b_key.2),
+            SortKey::Source => a_key.0.cmp(&b_key.0),
+        };
+        let order = if sort.descending {
+            order.reverse()
+        } else {
+            order
+        };
+        order.then_with(|| b.bytes.cmp(&a.bytes))
+    });
+    sorted
+}
+
+/// Feed column widths, measured over the rows currently listed.
+struct FeedWidths {
+    app: usize,
+    source: usize,
+    dest: usize,
+}
+
+impl FeedWidths {
+    /// Addresses longer than this (IPv6 with few zero groups) are abbreviated with an
+    /// ellipsis in the middle; the inspector still has the full form.
+    const MAX_ADDR: usize = 26;
+    const MAX_APP: usize = 20;
+
+    fn measure(packets: &[&PacketData], host_names: Option<&HashMap<IpAddr, String>>) -> Self {
+        let widest = |len: &dyn Fn(&PacketData) -> usize, max: usize| {
+            packets.iter().map(|p| len(p)).max().unwrap_or(0).min(max)
+        };
+        let chars = |text: &str| text.chars().count();
+        Self {
+            app: widest(&|p| chars(&p.app_name), Self::MAX_APP),
+            source: widest(
+                &|p| chars(host_label(&p.source, host_names)),
+                Self::MAX_ADDR,
+            ),
+            dest: widest(&|p| chars(host_label(&p.dest, host_names)), Self::MAX_ADDR),
+        }
+    }
+}
+
+/// `addr` as the feed shows it: its reverse-DNS name once one is known.
+fn host_label<'a>(addr: &'a str, host_names: Option<&'a HashMap<IpAddr, String>>) -> &'a str {
+    host_names
+        .zip(addr.parse::<IpAddr>().ok())
+        .and_then(|(names, ip)| names.get(&ip))
+        .map_or(addr, String::as_str)
+}
+
+/// Fits `addr` into `width` columns, keeping both ends: the network prefix and the
+/// interface ID are what tell IPv6 hosts apart.
+fn abbreviate(addr: &str, width: usize) -> String {
+    let len = addr.chars().count();
+    if len <= width || width < 3 {
+        return addr.to_string();
+    }
+    let tail = (width - 1) / 2;
+    let head = width - 1 - tail;
+    let chars: Vec<char> = addr.chars().collect();
+    format!(
+        "{}…{}",
+        chars[..head].iter().collect::<String>(),
+        chars[len - tail..].iter().collect::<String>()
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn draw_feed_tab(
+    f: &mut Frame,
+    area: Rect,
+    packets: &VecDeque<PacketData>,
+    filter: &str,
+    app_filter: Option<&str>,
+    enabled_chips: &HashSet<&str>,
+    keymap: &KeyMap,
+    list_state: &mut ListState,
+    spike_idx: Option<usize>,
+    history: &[u64],
+    pause_time: Option<Instant>,
+    compact: bool,
+    show_detail: bool,
+    show_length: bool,
+    absolute_time: bool,
+    pinned: Option<&PacketData>,
+    detail_view: DetailView,
+    text_limit: usize,
+    rate_unit: RateUnit,
+    host_names: Option<&HashMap<IpAddr, String>>,
+) -> usize {
+    let [chip_area, area] = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .areas(area);
+    draw_chips(f, chip_area, keymap, enabled_chips);
+
+    let has_subject = list_state.selected().is_some() || spike_idx.is_some() || pinned.is_some();
+    let (list_area, detail_area) = split_list_detail(area, compact, show_detail && has_subject);
+
+    let filtered = visible_packets(
+        packets,
+        filter,
+        app_filter,
+        enabled_chips,
+        spike_idx,
+        history.len(),
+        pause_time,
+    );
 
     // --- LEFT: LIST RENDERING ---
+    // Columns are as wide as the longest value on screen, so IPv6 isn't cut off and
+    // short IPv4/app names don't leave gaps
+    let widths = FeedWidths::measure(&filtered, host_names);
     let items: Vec<ListItem> = filtered
         .iter()
         .map(|p| {
-            ListItem::new(Line::from(vec![
-                Span::styled(format!("{:<12}", p.app_name), Style::default().fg(Color::Green)),
-                Span::raw(format!(" │ {}", p.summary)).white(),
-            ]))
+            let mut spans = vec![Span::styled(
+                format!("{:<width$.width$}", p.app_name, width = widths.app),
+                Style::default().fg(Color::Green),
+            )];
+            if show_length {
+                spans.push(Span::raw(format!(" │ {:>9}", format_bytes(p.length as u64))).cyan());
+            }
+            let summary = if p.proto_label == "UNPARSED" {
+                p.summary.clone()
+            } else {
+                format!(
+                    "{:<src$} -> {:<dst$} | {}{}",
+                    abbreviate(host_label(&p.source, host_names), widths.source),
+                    abbreviate(host_label(&p.dest, host_names), widths.dest),
+                    p.proto_label,
+                    p.info
+                        .as_ref()
+                        .map(|info| format!(" {}", info))
+                        .unwrap_or_default(),
+                    src = widths.source,
+                    dst = widths.dest
+                )
+            };
+            // Old TLS versions stand out so they get noticed in a busy feed
+            let summary_style = if p.deprecated_tls || p.zero_window {
+                Style::default().fg(Color::Red).bold()
+            } else if p.dscp.is_some_and(is_high_priority) {
+                // Voice/video/network-control markings (EF, AF4x, CS5+)
+                Style::default().fg(Color::Magenta)
+            } else {
+                Style::default().fg(protocol_color(&p.proto_label))
+            };
+            spans.push(Span::styled(format!(" │ {}", summary), summary_style));
+            if p.zero_window {
+                spans.push(Span::styled(
+                    " ZERO WINDOW ",
+                    Style::default().fg(Color::White).bg(Color::Red).bold(),
+                ));
+            }
+            // Lookup time on the response that answered a tracked query
+            if let Some(latency) = p.dns_latency {
+                let style = if latency >= SLOW_LOOKUP {
+                    Style::default().fg(Color::Red).bold()
+                } else {
+                    Style::default().fg(Color::DarkGray)
+                };
+                spans.push(Span::styled(format!(" {}ms", latency.as_millis()), style));
+            }
+            if p.dup_count > 0 {
+                spans.push(Span::raw(format!(" (dup x{})", p.dup_count)).dark_gray());
+            }
+            ListItem::new(Line::from(spans))
         })
         .collect();
 
     f.render_stateful_widget(
         List::new(items)
-            .block(Block::default().title(" PACKET STREAM ").borders(Borders::ALL).green())
+            .block(
+                Block::default()
+                    .title(" PACKET STREAM ")
+                    .borders(Borders::ALL)
+                    .green(),
+            )
             .highlight_style(Style::default().bg(Color::Rgb(40, 40, 40)).bold()),
-        chunks[0],
+        list_area,
         list_state,
     );
 
-    // --- RIGHT: DYNAMIC INSPECTOR ---
-    // If a specific packet is selected, show the Deep Inspector (Headers + Hex)
-    if let Some(p_idx) = list_state.selected() {
-        if let Some(packet) = filtered.get(p_idx) {
-            let display_text = format!(
-                "{}\n\n--- RAW PAYLOAD (HEX) ---\n{}", 
-                packet.full_details, 
-                packet.hex_dump
-            );
+    let Some(detail_area) = detail_area else {
+        return list_rows(list_area);
+    };
 
-            f.render_widget(
-                Paragraph::new(display_text)
-                    .block(Block::default().title(" 🔍 PACKET INSPECTOR ").borders(Borders::ALL).yellow())
-                    .wrap(Wrap { trim: false }),
-                chunks[1],
-            );
-        }
-    } 
+    // --- RIGHT: DYNAMIC INSPECTOR ---
+    // A pinned packet stays put while the selection moves, for side-by-side comparison;
+    // otherwise a selected packet gets the Deep Inspector (Headers + Hex)
+    let inspected = match pinned {
+        Some(packet) => Some((packet, " 📌 PINNED PACKET ")),
+        None => list_state
+            .selected()
+            .and_then(|p_idx| filtered.get(p_idx))
+            .map(|packet| (*packet, " 🔍 PACKET INSPECTOR ")),
+    };
+    if let Some((packet, title)) = inspected {
+        f.render_widget(
+            Paragraph::new(inspector_text(packet, detail_view, text_limit))
+                .block(
+                    Block::default()
+                        .title(format!("{}· {} ", title, detail_view.name()))
+                        .title(Line::from(format!(" {} ", packet.time_label)).right_aligned())
+                        .borders(Borders::ALL)
+                        .yellow(),
+                )
+                .wrap(Wrap { trim: false }),
+            detail_area,
+        );
+    }
     // Otherwise, if we are scrubbing through a spike, show the Spike Analysis
     else if let Some(s_idx) = spike_idx {
         let mut app_counts = HashMap::new();
@@ -217,152 +1314,275 @@ fn draw_feed_tab(
             .unwrap_or("None");
 
         let val = history.get(s_idx).cloned().unwrap_or(0);
+        let seconds_ago = history.len().saturating_sub(1 + s_idx) as i64;
+        // Same window visible_packets uses: the second that ended `seconds_ago` before the pause
+        let window = match pause_time.filter(|_| absolute_time) {
+            Some(paused_at) => {
+                let pause_wall =
+                    Local::now() - TimeDelta::from_std(paused_at.elapsed()).unwrap_or_default();
+                let end = pause_wall - TimeDelta::seconds(seconds_ago);
+                format!(
+                    "{} - {}",
+                    (end - TimeDelta::seconds(1)).format("%H:%M:%S"),
+                    end.format("%H:%M:%S")
+                )
+            }
+            None => format!("{}s ago", seconds_ago),
+        };
         let info = format!(
             "\n  --- 🔎 SPIKE ANALYSIS ---\n\n\
-              Target Window:   {}s ago\n\
+              Target Window:   {}\n\
               Total Load:      {}\n\
               Packet Count:    {}\n\n\
               --- 🏆 TOP TALKER ---\n\
               Primary App:      {}\n\n\
               --- ⌨️  NAVIGATION ---\n\
               [↑/↓] Browse specific packets\n\
-              [←/→] Shift time window",
-            history.len().saturating_sub(1 + s_idx),
-            format_bytes(val),
-            filtered.iter().count(),
-            top_app
+              [←/→] Shift time window\n\
+              [{}] Relative/absolute time",
+            window,
+            rate_unit.format(val),
+            filtered.len(),
+            top_app,
+            keymap.label(Action::ToggleSpikeTime)
         );
 
         f.render_widget(
             Paragraph::new(info)
-                .block(Block::default().title(" SPIKE SUMMARY ").borders(Borders::ALL).cyan().bold())
+                .block(
+                    Block::default()
+                        .title(" SPIKE SUMMARY ")
+                        .borders(Borders::ALL)
+                        .cyan()
+                        .bold(),
+                )
                 .wrap(Wrap { trim: false }),
-            chunks[1],
+            detail_area,
         );
-    } 
+    }
     // Default state: Nothing selected
     else {
         f.render_widget(
             Paragraph::new("\n\nSelect a packet or scrub the timeline to begin inspection.")
                 .dark_gray()
                 .centered(),
-            chunks[1],
+            detail_area,
         );
     }
+    list_rows(list_area)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn draw_connections_tab(
     f: &mut Frame,
     area: Rect,
-    connections: &HashMap<(String, String, String, String), u64>,
+    connections: &HashMap<ConnectionKey, ConnectionStats>,
+    remote_hosts: usize,
+    baseline: Option<&HashSet<ConnectionKey>>,
     throughput: &[u64],
     filter: &str,
+    app_filter: Option<&str>,
     list_state: &mut ListState,
     selected_idx: Option<usize>,
-) {
+    chart_scale: ChartScale,
+    rate_unit: RateUnit,
+    sort: ConnectionSort,
+    avg_window: usize,
+    throughput_peak: u64,
+    compact: bool,
+    show_detail: bool,
+) -> usize {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Length(10), Constraint::Min(0)])
         .split(area);
 
     let chart_width = chunks[0].width.saturating_sub(2);
-    let max_bars = (chart_width as usize) / 4;
-    let visible_history = if throughput.len() > max_bars {
-        &throughput[throughput.len() - max_bars..]
-    } else {
-        throughput
-    };
+    let max_bars = ((chart_width as usize) / 4).max(1);
+    // A history longer than the chart has room for is averaged down, each bar covering
+    // `per_bar` seconds. Groups are counted back from the newest second, so only the
+    // oldest bar can be partial.
+    let per_bar = throughput.len().div_ceil(max_bars).max(1);
+    let visible_history: Vec<u64> = throughput
+        .rchunks(per_bar)
+        .rev()
+        .map(|seconds| seconds.iter().sum::<u64>() / seconds.len() as u64)
+        .collect();
 
-    let visible_selected = selected_idx.and_then(|idx| {
-        let start = throughput.len().saturating_sub(max_bars);
-        if idx >= start {
-            Some(idx - start)
-        } else {
-            None
-        }
-    });
+    let visible_selected = selected_idx
+        .filter(|idx| *idx < throughput.len())
+        .map(|idx| visible_history.len() - 1 - (throughput.len() - 1 - idx) / per_bar);
 
-    let barchart_data: Vec<(&str, u64)> = visible_history
+    // Bar heights follow the scale; the printed value is always the real byte count
+    let bars: Vec<Bar> = visible_history
         .iter()
         .enumerate()
         .map(|(i, &v)| {
-            if Some(i) == visible_selected {
-                ("SEL", v)
-            } else {
-                ("", v)
-            }
+            let height = match chart_scale {
+                ChartScale::Auto => v,
+                ChartScale::Fixed(ceiling) => v.min(ceiling),
+                ChartScale::Log => log_height(v),
+            };
+            Bar::default()
+                .value(height)
+                .text_value(v.to_string())
+                .label(if Some(i) == visible_selected {
+                    "SEL"
+                } else {
+                    ""
+                })
         })
         .collect();
 
+    let mut title = match chart_scale {
+        ChartScale::Auto => " THROUGHPUT (auto) ".to_string(),
+        ChartScale::Fixed(ceiling) => format!(" THROUGHPUT (max {}) ", rate_unit.format(ceiling)),
+        ChartScale::Log => " THROUGHPUT (log) ".to_string(),
+    };
+    if per_bar > 1 {
+        title.push_str(&format!("· {}s per bar ", per_bar));
+    }
+    // Trend context: how the latest seconds compare with the session so far
+    let average = rolling_average(throughput, avg_window);
+    let trend = Line::from(vec![
+        Span::raw(format!(" avg {}s ", avg_window)),
+        Span::styled(
+            rate_unit.format(average),
+            Style::default().fg(Color::Yellow),
+        ),
+        Span::raw(" │ peak "),
+        Span::styled(
+            rate_unit.format(throughput_peak),
+            Style::default().fg(Color::Red),
+        ),
+        Span::raw(" "),
+    ])
+    .right_aligned();
+    let mut chart = BarChart::default()
+        .block(
+            Block::default()
+                .title(title)
+                .title(trend)
+                .borders(Borders::ALL)
+                .cyan(),
+        )
+        .data(BarGroup::default().bars(&bars));
+    // Same mapping ratatui uses: heights are relative to the pinned or tallest value
+    let (chart_max, average_height) = match chart_scale {
+        ChartScale::Fixed(ceiling) => (ceiling, average.min(ceiling)),
+        // Pin the log scale to 1 GB/s so bar heights are comparable over time
+        ChartScale::Log => (log_height(1 << 30), log_height(average)),
+        ChartScale::Auto => (visible_history.iter().copied().max().unwrap_or(0), average),
+    };
+    if chart_scale != ChartScale::Auto {
+        chart = chart.max(chart_max);
+    }
+
     f.render_widget(
-        BarChart::default()
-            .block(
-                Block::default()
-                    .title(" THROUGHPUT ")
-                    .borders(Borders::ALL)
-                    .cyan(),
-            )
-            .data(&barchart_data)
+        chart
             .bar_width(3)
             .bar_gap(1)
             .bar_style(Style::default().fg(Color::Cyan))
             .value_style(Style::default().fg(Color::Yellow)),
         chunks[0],
     );
+    draw_reference_line(f, chunks[0], average_height, chart_max);
 
-    let bottom_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-        .split(chunks[1]);
-
-    let mut sorted: Vec<_> = connections.iter().collect();
-    sorted.sort_by(|a, b| b.1.cmp(a.1));
-    let filtered_conns: Vec<_> = sorted
-        .into_iter()
-        .filter(|(key, _)| {
-            filter.is_empty()
-                || format!("{:?}", key)
-                    .to_lowercase()
-                    .contains(&filter.to_lowercase())
-        })
-        .collect();
+    let has_subject = list_state.selected().is_some() || selected_idx.is_some();
+    let (list_area, detail_area) =
+        split_list_detail(chunks[1], compact, show_detail && has_subject);
+
+    let filtered_conns = visible_connections(connections, filter, app_filter, sort);
 
     let items: Vec<ListItem> = filtered_conns
         .iter()
-        .map(|(key, bytes)| {
-            let (_src, _dst, proto, app) = key;
-            ListItem::new(Line::from(vec![
+        .map(|(key, stats)| {
+            let (src, dst, proto, app) = key;
+            let mut spans = vec![
                 Span::styled(format!("{:<10}", app), Style::default().fg(Color::Green)),
                 format!(" │ {} │ ", proto).into(),
-                Span::styled(format_bytes(**bytes), Style::default().fg(Color::Cyan)),
-            ]))
+                Span::styled(format_bytes(stats.bytes), Style::default().fg(Color::Cyan)),
+                Span::raw(format!(" │ {} pkts", stats.packets)).dark_gray(),
+            ];
+            if let Some(country) = &stats.country {
+                spans.insert(1, Span::raw(format!(" {}", country)).yellow());
+            }
+            if is_folded(src) || is_folded(dst) {
+                spans.push(Span::styled(
+                    format!(" │ {} connections", stats.flows),
+                    Style::default().fg(Color::Magenta),
+                ));
+            }
+            // Appeared since the last snapshot
+            if baseline.is_some_and(|known| !known.contains(*key)) {
+                spans.insert(
+                    0,
+                    Span::styled(" NEW ", Style::default().fg(Color::Black).bg(Color::Yellow)),
+                );
+                spans.insert(1, " ".into());
+            }
+            ListItem::new(Line::from(spans))
         })
         .collect();
 
+    // Session-wide scale, whatever the filter shows
+    let mut sessions_title = format!(
+        " SESSIONS · {} connections · {} remote hosts · by {} ",
+        connections.len(),
+        remote_hosts,
+        sort.name()
+    );
+    if let Some(known) = baseline {
+        sessions_title.push_str(&format!("· {} baselined ", known.len()));
+    }
     f.render_stateful_widget(
         List::new(items)
             .block(
                 Block::default()
-                    .title(" SESSIONS ")
+                    .title(sessions_title)
                     .borders(Borders::ALL)
                     .cyan(),
             )
             .highlight_style(Style::default().bg(Color::Rgb(40, 40, 40))),
-        bottom_chunks[0],
+        list_area,
         list_state,
     );
 
+    let Some(detail_area) = detail_area else {
+        return list_rows(list_area);
+    };
+
     if let Some(idx) = list_state.selected() {
-        if let Some((key, bytes)) = filtered_conns.get(idx) {
+        if let Some((key, stats)) = filtered_conns.get(idx) {
             let (src, dst, proto, app) = key;
-            let info = format!(
-                "Application: {}\nProtocol:    {}\nSource:      {}\nDestination: {}\nTotal Data:  {}",
+            let mut info = format!(
+                "Application: {}\nProtocol:    {}\nSource:      {}\nDestination: {}\nTotal Data:  {}\nUp / Down:   ↑ {} / ↓ {}\nPackets:     {}\nAvg Size:    {}",
                 app,
                 proto,
                 src,
                 dst,
-                format_bytes(**bytes)
+                format_bytes(stats.bytes),
+                format_bytes(stats.bytes_out),
+                format_bytes(stats.bytes_in),
+                stats.packets,
+                format_bytes(stats.bytes / stats.packets.max(1))
             );
+            if let Some(country) = &stats.country {
+                info.push_str(&format!("\nCountry:     {}", country));
+            }
+            if is_folded(src) || is_folded(dst) {
+                info.push_str(&format!(
+                    "\nFolded:      {} connections (ephemeral ports)",
+                    stats.flows
+                ));
+            }
+            if let (Some(first), Some(last)) = (stats.first_seen, stats.last_seen) {
+                info.push_str(&format!(
+                    "\nActive:      {} (last seen {}s ago)",
+                    format_duration(last.duration_since(first)),
+                    last.elapsed().as_secs()
+                ));
+            }
             f.render_widget(
                 Paragraph::new(info)
                     .block(
@@ -372,14 +1592,14 @@ fn draw_connections_tab(
                             .yellow(),
                     )
                     .wrap(Wrap { trim: false }),
-                bottom_chunks[1],
+                detail_area,
             );
         }
     } else if let Some(s_idx) = selected_idx {
         let val = throughput.get(s_idx).cloned().unwrap_or(0);
         let info = format!(
             "\n  --- 📊 SNAPSHOT OVERVIEW ---\n\n  Load:      {}\n  Index:     {}\n\n  This represents a cumulative\n  total for all connections\n  during this 1s interval.",
-            format_bytes(val),
+            rate_unit.format(val),
             s_idx
         );
         f.render_widget(
@@ -389,7 +1609,8 @@ fn draw_connections_tab(
                     .borders(Borders::ALL)
                     .cyan(),
             ),
-            bottom_chunks[1],
+            detail_area,
         );
     }
+    list_rows(list_area)
 }
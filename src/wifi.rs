@@ -0,0 +1,246 @@
+use crate::capture::format_mac;
+use pcap::Linktype;
+
+/// Decoded view of a single 802.11 frame, ready to be turned into a `PacketData`.
+pub struct WifiFrame {
+    pub source: String,
+    pub dest: String,
+    pub proto_label: String,
+    pub details: String,
+}
+
+pub fn is_wireless(linktype: Linktype) -> bool {
+    matches!(
+        linktype,
+        Linktype::IEEE802_11 | Linktype::IEEE802_11_RADIOTAP
+    )
+}
+
+pub fn decode_wifi_frame(data: &[u8], linktype: Linktype) -> Option<WifiFrame> {
+    let mut details = String::new();
+
+    let frame = if linktype == Linktype::IEEE802_11_RADIOTAP {
+        let radiotap = parse_radiotap(data)?;
+        details.push_str("--- RADIOTAP ---\n");
+        match radiotap.channel_mhz {
+            Some(mhz) => details.push_str(&format!(
+                "Channel:  {} ({} MHz)\n",
+                channel_from_mhz(mhz),
+                mhz
+            )),
+            None => details.push_str("Channel:  n/a\n"),
+        }
+        match radiotap.signal_dbm {
+            Some(dbm) => details.push_str(&format!("Signal:   {} dBm\n", dbm)),
+            None => details.push_str("Signal:   n/a\n"),
+        }
+        if let Some(rate) = radiotap.rate_500kbps {
+            details.push_str(&format!("Rate:     {:.1} Mb/s\n", rate as f32 / 2.0));
+        }
+
+        let frame = &data[radiotap.header_len..];
+        // Drop the trailing FCS so it doesn't get mistaken for tagged parameters
+        if radiotap.has_fcs && frame.len() >= 4 {
+            &frame[..frame.len() - 4]
+        } else {
+            frame
+        }
+    } else {
+        data
+    };
+
+    // Frame Control (2) + Duration (2) + Address 1 (6) is the shortest header (ACK/CTS)
+    if frame.len() < 10 {
+        return None;
+    }
+
+    let frame_type = (frame[0] >> 2) & 0b11;
+    let subtype = frame[0] >> 4;
+    let flags = frame[1];
+
+    let addr1 = format_mac(&frame[4..10]);
+    let addr2 = frame.get(10..16).map(format_mac);
+    let addr3 = frame.get(16..22).map(format_mac);
+
+    let (type_name, subtype_name) = frame_names(frame_type, subtype);
+    details.push_str("\n--- 802.11 ---\n");
+    details.push_str(&format!("Type:     {} / {}\n", type_name, subtype_name));
+    details.push_str(&format!(
+        "Flags:    {}{}{}\n",
+        if flags & 0x01 != 0 { "ToDS " } else { "" },
+        if flags & 0x02 != 0 { "FromDS " } else { "" },
+        if flags & 0x40 != 0 { "Protected" } else { "" }
+    ));
+    details.push_str(&format!("Addr1:    {}\n", addr1));
+    if let Some(a) = &addr2 {
+        details.push_str(&format!("Addr2:    {}\n", a));
+    }
+    if let Some(a) = &addr3 {
+        details.push_str(&format!("Addr3:    {}\n", a));
+    }
+
+    let mut proto_label = format!("WiFi {}", subtype_name);
+
+    // Management frames carry tagged parameters; the SSID is tag 0
+    if frame_type == 0 {
+        let fixed_len = match subtype {
+            0 => Some(4),      // Association request: capability + listen interval
+            2 => Some(10),     // Reassociation request: + current AP
+            4 => Some(0),      // Probe request: tags start right away
+            5 | 8 => Some(12), // Probe response / beacon: timestamp + interval + capability
+            _ => None,
+        };
+        if let Some(fixed_len) = fixed_len
+            && let Some(tags) = frame.get(24 + fixed_len..)
+            && let Some(ssid) = find_ssid(tags)
+        {
+            if ssid.is_empty() {
+                details.push_str("SSID:     <wildcard/hidden>\n");
+            } else {
+                details.push_str(&format!("SSID:     {}\n", ssid));
+                proto_label = format!("WiFi {} \"{}\"", subtype_name, ssid);
+            }
+        }
+    }
+
+    Some(WifiFrame {
+        source: addr2.unwrap_or_else(|| String::from("Unknown")),
+        dest: addr1,
+        proto_label,
+        details,
+    })
+}
+
+struct Radiotap {
+    header_len: usize,
+    has_fcs: bool,
+    rate_500kbps: Option<u8>,
+    channel_mhz: Option<u16>,
+    signal_dbm: Option<i8>,
+}
+
+fn parse_radiotap(data: &[u8]) -> Option<Radiotap> {
+    if data.len() < 8 || data[0] != 0 {
+        return None;
+    }
+    let header_len = u16::from_le_bytes([data[2], data[3]]) as usize;
+    if header_len > data.len() {
+        return None;
+    }
+
+    // Skip over any extended presence bitmaps (bit 31 chains to another word)
+    let present = u32::from_le_bytes([data[4], data[5], data[6], data[7]]);
+    let mut offset = 8;
+    let mut word = present;
+    while word & (1 << 31) != 0 {
+        let next = data.get(offset..offset + 4)?;
+        word = u32::from_le_bytes([next[0], next[1], next[2], next[3]]);
+        offset += 4;
+    }
+
+    let mut radiotap = Radiotap {
+        header_len,
+        has_fcs: false,
+        rate_500kbps: None,
+        channel_mhz: None,
+        signal_dbm: None,
+    };
+
+    // Fields appear in bit order, each aligned to its natural size. We only walk
+    // as far as the antenna signal (bit 5), which is all the UI needs.
+    let align = |offset: usize, to: usize| offset.div_ceil(to) * to;
+
+    if present & (1 << 0) != 0 {
+        offset = align(offset, 8) + 8; // TSFT
+    }
+    if present & (1 << 1) != 0 {
+        radiotap.has_fcs = data.get(offset).is_some_and(|f| f & 0x10 != 0);
+        offset += 1;
+    }
+    if present & (1 << 2) != 0 {
+        radiotap.rate_500kbps = data.get(offset).copied();
+        offset += 1;
+    }
+    if present & (1 << 3) != 0 {
+        offset = align(offset, 2);
+        radiotap.channel_mhz = data
+            .get(offset..offset + 2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]));
+        offset += 4;
+    }
+    if present & (1 << 4) != 0 {
+        offset += 2; // FHSS
+    }
+    if present & (1 << 5) != 0 && offset < header_len {
+        radiotap.signal_dbm = data.get(offset).map(|&b| b as i8);
+    }
+
+    Some(radiotap)
+}
+
+fn find_ssid(mut tags: &[u8]) -> Option<String> {
+    while tags.len() >= 2 {
+        let (id, len) = (tags[0], tags[1] as usize);
+        let value = tags.get(2..2 + len)?;
+        if id == 0 {
+            return Some(String::from_utf8_lossy(value).into_owned());
+        }
+        tags = &tags[2 + len..];
+    }
+    None
+}
+
+fn channel_from_mhz(mhz: u16) -> u16 {
+    match mhz {
+        2484 => 14,
+        2412..=2472 => (mhz - 2407) / 5,
+        5955..=7115 => (mhz - 5950) / 5,
+        5000..=5900 => (mhz - 5000) / 5,
+        _ => 0,
+    }
+}
+
+fn frame_names(frame_type: u8, subtype: u8) -> (&'static str, &'static str) {
+    match frame_type {
+        0 => (
+            "Management",
+            match subtype {
+                0 => "assoc-req",
+                1 => "assoc-resp",
+                2 => "reassoc-req",
+                3 => "reassoc-resp",
+                4 => "probe-req",
+                5 => "probe-resp",
+                8 => "beacon",
+                9 => "atim",
+                10 => "disassoc",
+                11 => "auth",
+                12 => "deauth",
+                13 => "action",
+                _ => "mgmt",
+            },
+        ),
+        1 => (
+            "Control",
+            match subtype {
+                8 => "block-ack-req",
+                9 => "block-ack",
+                10 => "ps-poll",
+                11 => "rts",
+                12 => "cts",
+                13 => "ack",
+                _ => "ctrl",
+            },
+        ),
+        2 => (
+            "Data",
+            match subtype {
+                4 => "null",
+                8 => "qos-data",
+                12 => "qos-null",
+                _ => "data",
+            },
+        ),
+        _ => ("Extension", "ext"),
+    }
+}